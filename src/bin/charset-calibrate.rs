@@ -0,0 +1,317 @@
+/**
+ * Offline tool which makes a charset 'ramp' (see the main program's `--charset` option) for a given font.
+ *
+ * Renders each candidate glyph from a BDF bitmap font into a character cell, measures its ink coverage,
+ * and prints the candidates ordered by coverage, thinned out so that they're spread evenly across the range.
+ *
+ * Uses the bundled 'Ramp Mono' font when no font is given. Other fonts can be converted to BDF
+ * with tools like `otf2bdf`, at the size the terminal uses.
+ */
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::process;
+
+
+static USAGE_TEXT: &'static str = "\
+Usage: charset-calibrate [options]
+
+Options:
+  --font <file>      BDF bitmap font to measure (default: the bundled Ramp Mono)
+  --chars <chars>    candidate chars (default: all printable ASCII in the font)
+  --length <n>       number of chars in the ramp (default: all of the candidates,
+                     minus any with the same coverage as another)
+  --verbose          also list each candidate's coverage
+  --help             show this text
+";
+
+static BUNDLED_FONT: &'static str = include_str!("../fract/res/fonts/ramp-mono.bdf");
+
+
+fn main() {
+
+	let mut font_path = None;
+	let mut chars = None;
+	let mut length = None;
+	let mut is_verbose = false;
+
+	let args: Vec<String> = env::args().skip(1).collect();
+	let mut i = 0;
+	while i < args.len() {
+		let arg = &args[i];
+		if arg == "--help" || arg == "-h" {
+			exit_with_usage(None);
+		}
+		if arg == "--verbose" {
+			is_verbose = true;
+			i += 1;
+			continue;
+		}
+		i += 1;
+		if i >= args.len() {
+			exit_with_usage(Some(format!("Missing value for {}", arg)));
+		}
+		let value = args[i].clone();
+		match &arg[..] {
+			"--font" => font_path = Some(value),
+			"--chars" => chars = Some(value),
+			"--length" => {
+				match value.parse::<usize>() {
+					Ok(n) if n >= 2 => length = Some(n),
+					_ => exit_with_usage(Some(format!("Bad value for length: {}", value)))
+				}
+			},
+			_ => exit_with_usage(Some(format!("Unexpected argument: {}", arg)))
+		}
+		i += 1;
+	}
+
+	let text = match font_path {
+		Some(ref path) => {
+			let mut text = String::new();
+			match File::open(path) {
+				Ok(mut file) => match file.read_to_string(&mut text) {
+					Ok(_) => {},
+					Err(e) => exit_with_error(format!("Couldn't read {}: {}", path, e))
+				},
+				Err(e) => exit_with_error(format!("Couldn't open {}: {}", path, e))
+			}
+			text
+		},
+		None => BUNDLED_FONT.to_string()
+	};
+	let font = match Font::parse(&text) {
+		Ok(f) => f,
+		Err(e) => exit_with_error(format!("Couldn't parse font: {}", e))
+	};
+
+	let candidates: Vec<char> = match chars {
+		Some(ref s) => s.chars().collect(),
+		None => (32u8..127).map(|b| b as char).collect()
+	};
+
+	// measure
+	let mut measured = Vec::new();
+	for &c in candidates.iter() {
+		match font.glyph(c) {
+			Some(glyph) => measured.push((c, font.coverage(glyph))),
+			None => {
+				if chars.is_some() {
+					eprintln!("(no glyph for '{}')", c);
+				}
+			}
+		}
+	}
+	if measured.len() < 2 {
+		exit_with_error("Need at least 2 candidates which are in the font".to_string());
+	}
+	measured.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+	measured.dedup_by_key(|m| (m.1 * 1_000_000.0) as i64);
+
+	if is_verbose {
+		for &(c, coverage) in measured.iter() {
+			println!("'{}' {:.4}", c, coverage);
+		}
+		println!("");
+	}
+
+	let ramp = match length {
+		Some(n) => spread(&measured, n),
+		None => measured.iter().map(|m| m.0).collect()
+	};
+	let ramp: String = ramp.into_iter().collect();
+	println!("charset = \"{}\"", ramp);
+}
+
+/**
+ * Picks `n` of the (sorted) measured chars, aiming for evenly spaced coverage values,
+ * always including the lightest and the heaviest
+ */
+fn spread(measured: &Vec<(char, f64)>, n: usize) -> Vec<char> {
+
+	if n >= measured.len() {
+		return measured.iter().map(|m| m.0).collect();
+	}
+	let min = measured[0].1;
+	let max = measured[measured.len() - 1].1;
+
+	let mut result = Vec::new();
+	let mut next = 0;  // (each pick comes after the previous one)
+	for i in 0..n {
+		let target = min + (max - min) * i as f64 / (n - 1) as f64;
+		// leave enough candidates for the rest of the picks
+		let last = measured.len() - (n - i);
+		let mut best = next;
+		for j in next..(last + 1) {
+			if (measured[j].1 - target).abs() < (measured[best].1 - target).abs() {
+				best = j;
+			}
+		}
+		result.push(measured[best].0);
+		next = best + 1;
+	}
+	result
+}
+
+fn exit_with_usage(message: Option<String>) -> ! {
+	match message {
+		Some(s) => {
+			eprintln!("{}\n", s);
+			eprint!("{}", USAGE_TEXT);
+			process::exit(1);
+		},
+		None => {
+			// (asked for with --help)
+			print!("{}", USAGE_TEXT);
+			process::exit(0);
+		}
+	}
+}
+
+fn exit_with_error(message: String) -> ! {
+	eprintln!("{}", message);
+	process::exit(1);
+}
+
+
+struct Glyph {
+	encoding: u32,
+	// bounding box, with x and y offsets from the origin (y going up)
+	w: i32,
+	h: i32,
+	x: i32,
+	y: i32,
+	rows: Vec<Vec<bool>>,  // from the top
+}
+
+/**
+ * The parts of a BDF font which matter for measuring coverage
+ */
+struct Font {
+	cell_w: i32,
+	ascent: i32,
+	descent: i32,
+	glyphs: Vec<Glyph>,
+}
+
+impl Font {
+
+	fn parse(text: &str) -> Result<Font, String> {
+
+		let mut font = Font { cell_w: 0, ascent: 0, descent: 0, glyphs: Vec::new() };
+		let mut glyph: Option<Glyph> = None;
+		let mut in_bitmap = false;
+
+		for line in text.lines() {
+			let v: Vec<&str> = line.split_whitespace().collect();
+			if v.len() == 0 {
+				continue;
+			}
+			let nums: Vec<i32> = v[1..].iter().filter_map(|s| s.parse::<i32>().ok()).collect();
+
+			if in_bitmap {
+				if v[0] == "ENDCHAR" {
+					in_bitmap = false;
+					match glyph.take() {
+						Some(g) => font.glyphs.push(g),
+						None => {}
+					}
+				} else {
+					match glyph {
+						Some(ref mut g) => {
+							let bits = match Font::parse_hex_row(v[0], g.w) {
+								Some(bits) => bits,
+								None => return Err(format!("Bad bitmap row: {}", line))
+							};
+							g.rows.push(bits);
+						},
+						None => {}
+					}
+				}
+				continue;
+			}
+
+			match (v[0], nums.len()) {
+				("FONTBOUNDINGBOX", 4) => {
+					font.cell_w = nums[0];
+					// (used if there's no FONT_ASCENT/FONT_DESCENT)
+					if font.ascent == 0 && font.descent == 0 {
+						font.ascent = nums[1] + nums[3];
+						font.descent = -nums[3];
+					}
+				},
+				("FONT_ASCENT", 1) => font.ascent = nums[0],
+				("FONT_DESCENT", 1) => font.descent = nums[0],
+				("STARTCHAR", _) => glyph = Some(Glyph { encoding: 0, w: 0, h: 0, x: 0, y: 0, rows: Vec::new() }),
+				("ENCODING", n) if n >= 1 => {
+					match glyph {
+						Some(ref mut g) if nums[0] >= 0 => g.encoding = nums[0] as u32,
+						_ => glyph = None  // (unencoded)
+					}
+				},
+				("BBX", 4) => {
+					match glyph {
+						Some(ref mut g) => {
+							g.w = nums[0];
+							g.h = nums[1];
+							g.x = nums[2];
+							g.y = nums[3];
+						},
+						None => {}
+					}
+				},
+				("BITMAP", _) => in_bitmap = true,
+				_ => {}
+			}
+		}
+
+		if font.cell_w <= 0 || font.ascent + font.descent <= 0 {
+			return Err("Missing FONTBOUNDINGBOX".to_string());
+		}
+		Ok(font)
+	}
+
+	fn parse_hex_row(s: &str, w: i32) -> Option<Vec<bool>> {
+		let mut bits = Vec::new();
+		for c in s.chars() {
+			let nibble = match c.to_digit(16) {
+				Some(n) => n,
+				None => return None
+			};
+			for i in 0..4 {
+				bits.push((nibble >> (3 - i)) & 1 == 1);
+			}
+		}
+		bits.truncate(w.max(0) as usize);
+		Some(bits)
+	}
+
+	fn glyph(&self, c: char) -> Option<&Glyph> {
+		self.glyphs.iter().find(|g| g.encoding == c as u32)
+	}
+
+	/**
+	 * Renders the glyph into a character cell (clipping anything outside of it),
+	 * and returns the ratio of the cell's pixels which are inked
+	 */
+	fn coverage(&self, glyph: &Glyph) -> f64 {
+
+		let cell_h = self.ascent + self.descent;
+		let mut cell = vec![false; (self.cell_w * cell_h) as usize];
+
+		for (row_index, row) in glyph.rows.iter().enumerate() {
+			// the glyph's top row is at (y + h - 1) above the baseline
+			let cell_y = self.ascent - (glyph.y + glyph.h) + row_index as i32;
+			for (col, &bit) in row.iter().enumerate() {
+				let cell_x = glyph.x + col as i32;
+				if bit && cell_x >= 0 && cell_x < self.cell_w && cell_y >= 0 && cell_y < cell_h {
+					cell[(cell_y * self.cell_w + cell_x) as usize] = true;
+				}
+			}
+		}
+
+		let inked = cell.iter().filter(|&&b| b).count();
+		inked as f64 / cell.len() as f64
+	}
+}
@@ -1,14 +1,38 @@
 extern crate num; 
 use self::num::complex::{Complex64};
 use leelib::vector2::Vector2f;
-use leelib::matrix::Matrix;
 use leelib::animator::{Anim, Animator};
 use fract::constants;
 use fract::TextBuffer;
 use fract::CoordList;
-use fract::input::Command;
-use fract::view::{View, MandelView, JuliaView, Views};
-use fract::fractalcalc::{FractalSpecs, FractalType};
+use fract::config::Config;
+use fract::input::{Command, Prompt};
+use fract::view::{View, MandelView, JuliaView, NewtonView, BuddhaView, LyapunovView, PointCloudView, Views};
+use fract::newton::Polynomial;
+use fract::formula::Formula;
+use fract::lyapunov::Sequence;
+use fract::attractor::PointSystem;
+use fract::fractalcalc::{FractalSpecs, FractalType, CalcStats, Supersample, SamplePattern, SampleReduce, RenderMode};
+use fract::progressive::Upscale;
+use fract::exposure::ExposureMode;
+use fract::asciifier::Mapping;
+use fract::tonecurve::ToneCurve;
+use fract::charset::CharsetList;
+use fract::minimap::Minimap;
+use fract::hud::{Hud, HudInfo, HudPosition};
+use fract::location::Location;
+use fract::transition::{Transition, TransitionStyle, ViewPosition};
+use fract::history::{self, CameraState, History, HistoryFile};
+use fract::undo::{UndoState, UndoStack};
+use leelib::ansi;
+use std::cmp;
+
+
+// split view: the indices of the views in the left and right panes (mandelbrot, julia)
+const SPLIT_PANES: (usize, usize) = (0, 1);
+
+// auto ramp cycling: the number of frames per step
+const RAMP_CYCLE_FRAMES: u32 = 4;
 
 
 pub struct App<'a> {
@@ -17,9 +41,9 @@ pub struct App<'a> {
 	view_height: usize,
 
     text_buffer: TextBuffer<'a>,
-	interview_animator: Animator<f64>,
-	interview_matrix: Matrix<u8>,
-	interview_last_index: usize,
+	// between views, locations, charsets, etc
+	transition: Option<Transition>,
+	transition_style: TransitionStyle,
 
 	has_shown_help: bool,
 	help_anim: Animator<f64>,
@@ -27,16 +51,48 @@ pub struct App<'a> {
 	feedback_string: String,
 	feedback_countdown: i32,
 
+	config: Config,
+	is_calibrating: bool,
+	
+	// which prompt is open, if any, and the text being typed into it
+	prompt: Option<(Prompt, String)>,
+	// the custom formula's source text, or empty when using the built-in formula
+	formula_text: String,
+	
+	// split view: the mandelbrot pane's center, as last copied over to the julia pane's seed
+	linked_center: Option<Vector2f>,
+	
+	minimap: Minimap,
+	is_hud_on: bool,
+	
+	// what the tone curve key uses for `ToneCurve::Piecewise`
+	piecewise_curve: ToneCurve,
+	is_ramp_cycling: bool,
+	charsets: CharsetList,
+	
+	// navigation history, one per view
+	histories: Vec<History>,
+	// the focused view's camera as of the last frame, and how many frames it's been still for 
+	last_camera: Option<CameraState>,
+	still_frames: u32,
+	
+	// camera (etc) states from before resets and jumps
+	undo_stack: UndoStack,
+
 	count: u32,
 }
 
 
 impl<'a> App<'a> {
 	
-	pub fn new() -> App<'a> {
+	/**
+	 * element_ar - the width/height ratio of a character cell
+	 */
+	pub fn new(config: Config, element_ar: f64) -> App<'a> {
 		
 	    let view_width = 80 as usize;
 	    let view_height = 24 as usize;  
+	    let charsets = CharsetList::new(&config.charsets);
 
 		let mut app = App {
 
@@ -45,9 +101,8 @@ impl<'a> App<'a> {
 			view_height: view_height,
 			
 		    text_buffer: TextBuffer::new(view_width, view_height),
-			interview_animator: Animator::<f64>::new(1.0, Anim::None),
-			interview_matrix: Matrix::new(view_width, view_height),
-			interview_last_index: 0,
+			transition: None,
+			transition_style: TransitionStyle::Crossfade,
 			
 			has_shown_help: false,
 			help_anim: Animator::<f64>::new(1.0, Anim::None),
@@ -55,19 +110,100 @@ impl<'a> App<'a> {
 			feedback_string: "".to_string(),
 			feedback_countdown: 0,
 			
+			config: config,
+			is_calibrating: false,
+			
+			prompt: None,
+			formula_text: "".to_string(),
+			
+			linked_center: None,
+			
+			minimap: Minimap::new(),
+			is_hud_on: false,
+			
+			piecewise_curve: ToneCurve::default_piecewise(),
+			is_ramp_cycling: false,
+			charsets: charsets,
+			
+			histories: Vec::new(),
+			last_camera: None,
+			still_frames: 0,
+			
+			undo_stack: UndoStack::new(),
+			
 			count: 0,
 		};
 		
 		// add the views to app
-		let v1 = MandelView::new(view_width, view_height, FractalSpecs::new_mandelbrot_with_defaults(constants::CHARACTER_ASPECT_RATIO));
+		let v1 = MandelView::new(view_width, view_height, FractalSpecs::new_mandelbrot_with_defaults(element_ar));
 		app.views.vec.push(Box::new(v1));
 
 		let julia_coordlist = CoordList::<Complex64>::new(constants::JULIA_COMPLEX_TEXT);
 		let default_julia_coord = julia_coordlist.get(1).clone();
-		let v2 = JuliaView::new(view_width, view_height, FractalSpecs::new_julia(default_julia_coord, constants::CHARACTER_ASPECT_RATIO));
+		let v2 = JuliaView::new(view_width, view_height, FractalSpecs::new_julia(default_julia_coord, element_ar));
 		app.views.vec.push(Box::new(v2));
+
+		let polynomial = match app.config.newton_polynomial {
+			Some(p) => p,
+			None => CoordList::<Polynomial>::new(constants::NEWTON_POLYNOMIALS_TEXT).get(0)
+		};
+		let v3 = NewtonView::new(view_width, view_height, FractalSpecs::new_newton(polynomial, element_ar));
+		app.views.vec.push(Box::new(v3));
+
+		let v4 = BuddhaView::new(view_width, view_height, FractalSpecs::new_buddhabrot(element_ar));
+		app.views.vec.push(Box::new(v4));
+
+		let sequence = match app.config.lyapunov_sequence {
+			Some(s) => s,
+			None => CoordList::<Sequence>::new(constants::LYAPUNOV_SEQUENCES_TEXT).get(0)
+		};
+		let v5 = LyapunovView::new(view_width, view_height, FractalSpecs::new_lyapunov(sequence, element_ar));
+		app.views.vec.push(Box::new(v5));
+
+		let system = CoordList::<PointSystem>::new(constants::POINT_SYSTEMS_TEXT).get(0);
+		let v6 = PointCloudView::new(view_width, view_height, FractalSpecs::new_point_cloud(system, element_ar));
+		app.views.vec.push(Box::new(v6));
 		
 		app.views.index = 0;
+		
+		let ids = app.view_ids();
+		app.histories = HistoryFile::load(&ids);
+		
+		let formula_text = app.config.formula_text.clone();
+		if formula_text.len() > 0 {
+			app.apply_formula(&formula_text);
+		}
+
+		if app.config.charsets.len() > 0 {
+			app.apply_charset();
+		}
+
+		match app.config.tone_curve.clone() {
+			Some(curve) => {
+				match curve {
+					ToneCurve::Piecewise(_) => app.piecewise_curve = curve.clone(),
+					_ => {}
+				}
+				for i in 0..app.views.vec.len() {
+					app.views.get_num(i).asciifier_m().set_tone_curve(curve.clone());
+				}
+			},
+			None => {}
+		}
+
+		// (the curves chosen in earlier sessions win over the config's)
+		for i in 0..app.views.vec.len() {
+			match app.histories[i].tone_curve.clone() {
+				Some(curve) => app.views.get_num(i).asciifier_m().set_tone_curve(curve),
+				None => {}
+			}
+		}
+
+		match app.config.warnings.len() {
+			0 => {},
+			1 => app.show_feedback(app.config.warnings[0].clone()),
+			n => app.show_feedback(format!("{} (and {} more, listed on exit)", app.config.warnings[0], n - 1))
+		}
 
 		app
 
@@ -80,6 +216,23 @@ impl<'a> App<'a> {
 	
 	pub fn handle_command(&mut self, command: &Command) {
 
+		match *command {
+			Command::PromptOpen(_) | Command::PromptChar(_) | Command::PromptBackspace | 
+					Command::PromptEnter | Command::PromptCancel => {
+				self.handle_prompt_command(command);
+				return;
+			},
+			_ => {}
+		}
+
+		if self.is_calibrating {
+			self.handle_calibration_command(command);
+			return;
+		}
+
+		// commands which can throw away a position push this onto the undo stack, once they've actually changed something
+		let undo_state = self.undo_state();
+
 		let vel_increment = self.views.get().width_animator().value as f64 * constants::VELOCITY_RATIO_INCREMENT;  // abstract this
 
 		// coord anim, start and stop
@@ -89,13 +242,15 @@ impl<'a> App<'a> {
 					Command::Coord(index) => {
 						let b = self.views.get().start_coord_anim(index);
 						if b {
+							self.undo_stack.push(undo_state);
 							self.show_feedback(format!("Starting Mandelbrot zoom {}", (index + 1)).to_string());
 						}
 					},
-					Command::RotationalVelocity(_) | Command::AutoExposure | Command::Help | Command::Size(..) => {} 
 					_ => {
-						// any command aside from the above turns off coord anim 
-						self.views.get().stop_coord_anim();
+						// any other command turns off coord anim
+						if ! command.keeps_coord_anim() {
+							self.views.get().stop_coord_anim();
+						}
 					}
 				}
 			},
@@ -104,6 +259,7 @@ impl<'a> App<'a> {
 					Command::Coord(index) => {
 						let b = self.views.get().start_coord_anim(index);
 						if b {
+							self.undo_stack.push(undo_state);
 							self.show_feedback(format!("Morphing to Julia set {}", (index + 1)).to_string());
 						}
 					},
@@ -112,6 +268,43 @@ impl<'a> App<'a> {
 					},
 					_ => { }
 				}
+			},
+			FractalType::Newton(..) => {
+				match *command {
+					Command::Coord(index) => {
+						let b = self.views.get().start_coord_anim(index);
+						if b {
+							self.undo_stack.push(undo_state);
+							let p = CoordList::<Polynomial>::new(constants::NEWTON_POLYNOMIALS_TEXT).get(index);
+							self.show_feedback(format!("Morphing to polynomial {}: {}", (index + 1), p).to_string());
+						}
+					},
+					Command::Reset | Command::Stop | Command::ChangeFractalSet => {
+						self.views.get().stop_coord_anim();
+					},
+					_ => { }
+				}
+			},
+			FractalType::Buddhabrot(..) | FractalType::Lyapunov(..) | FractalType::PointCloud(..) => {
+				match *command {
+					Command::Coord(index) => {
+						// (these switch over at once, rather than animating)
+						self.start_transition(None);
+						let b = self.views.get().start_coord_anim(index);
+						if b {
+							self.undo_stack.push(undo_state);
+							let s = match self.views.get().specs().fractal_type {
+								FractalType::Buddhabrot(mode) => format!("Mode: {}", mode.name()),
+								FractalType::Lyapunov(sequence) => format!("Sequence {}: {}", (index + 1), sequence),
+								FractalType::PointCloud(..) => format!("System {}: {}", (index + 1), 
+										CoordList::<PointSystem>::new(constants::POINT_SYSTEMS_TEXT).get(index)),
+								_ => "".to_string()
+							};
+							self.show_feedback(s);
+						}
+					},
+					_ => { }
+				}
 			}
 		} 
 
@@ -132,11 +325,27 @@ impl<'a> App<'a> {
 			},
 			Command::PositionTween(char_col, char_row) => {
 
-				let screen_center_x = self.view_width as f64 / 2.0;
+				// (in split view, clicking a pane also focuses it)
+				let (pane_x, pane_w) = match self.views.panes {
+					Some((left, right)) => {
+						let (left_w, right_w) = self.pane_widths();
+						if char_col as usize > left_w {
+							self.views.index = right;
+							(left_w + 1, right_w)
+						} else {
+							self.views.index = left;
+							(0, left_w)
+						}
+					},
+					None => (0, self.view_width)
+				};
+				let char_col = char_col - pane_x as i32;
+
+				let screen_center_x = pane_w as f64 / 2.0;
 				let screen_offset_ratio_x = (char_col as f64 - screen_center_x) / screen_center_x;
 				
 				// y requires extra logic:
-				let ar = self.view_width as f64 / self.view_height as f64;
+				let ar = pane_w as f64 / self.view_height as f64;
 				let viewport_height = self.views.get().width_animator().value * (1.0 / ar)  *  (1.0 / self.views.get().specs().element_ar);
 				let screen_center_y = self.view_height as f64 / 2.0;
 				let screen_offset_ratio_y = (char_row as f64 - screen_center_y) / screen_center_y;
@@ -179,13 +388,36 @@ impl<'a> App<'a> {
 			
 			Command::Stop => { 
 				self.stop_view_anims();
+				self.views.get().set_orbiting(false);
 			},
 			
 			Command::Reset => { 
+				self.undo_stack.push(undo_state);
 				self.views.get().anim_to_home();
+				self.views.get().set_orbiting(false);
+			},
+			Command::SeedPath => {
+				let mode = self.views.get().seed_path_mode().next();
+				self.views.get().set_seed_path_mode(mode);
+				let s = match self.views.get().specs().fractal_type {
+					FractalType::Julia(..) => format!("[S] Julia seed path: {}", mode.name()),
+					_ => "[S] Julia seed path (n/a for this fractal)".to_string()
+				};
+				self.show_feedback(s);
+			},
+			Command::Orbit => {
+				let b = ! self.views.get().is_orbiting();
+				self.views.get().set_orbiting(b);
+				let s = match self.views.get().specs().fractal_type {
+					FractalType::Julia(..) => if b { "[Shift-S] Julia seed orbit on" } else { "[Shift-S] Julia seed orbit off" },
+					_ => "[Shift-S] Julia seed orbit (n/a for this fractal)"
+				};
+				self.show_feedback(s.to_string());
 			},
 			Command::AutoExposure => { 
+				self.undo_stack.push(undo_state);
 				self.views.get().toggle_use_exposure();
+				self.views.get().set_exposure_lock(None);
 				
 				let s = if self.views.get().use_exposure() {
 					"[E] Auto-exposure on" 
@@ -194,10 +426,237 @@ impl<'a> App<'a> {
 				};
 				self.show_feedback(s.to_string());
 			} 
+			Command::ExposureMode => {
+				self.undo_stack.push(undo_state);
+				let mode = self.views.get().exposure_mode().next();
+				self.views.get().set_exposure_mode(mode);
+				let s = if mode == ExposureMode::Equalize && self.views.get().specs().mapping() != Mapping::Bias {
+					format!("[#] Exposure: {} (n/a for this render mode)", mode.name())
+				} else {
+					format!("[#] Exposure: {}", mode.name())
+				};
+				self.show_feedback(s);
+			}
+			Command::ExposureLock => {
+				let s = match self.views.get().exposure_lock() {
+					Some(_) => {
+						self.views.get().set_exposure_lock(None);
+						"[L] Exposure unlocked".to_string()
+					},
+					None => {
+						self.views.get().adjust_exposure_lock(0.0, 1.0);
+						self.exposure_lock_info("[L] Exposure locked")
+					}
+				};
+				self.show_feedback(s);
+			}
+			Command::ExposureShift(shift) => {
+				self.views.get().adjust_exposure_lock(shift, 1.0);
+				let s = self.exposure_lock_info(if shift < 0.0 { "[B] Brighter" } else { "[Shift-B] Darker" });
+				self.show_feedback(s);
+			}
+			Command::ExposureScale(scale) => {
+				self.views.get().adjust_exposure_lock(0.0, scale);
+				let s = self.exposure_lock_info(if scale < 1.0 { "[N] Narrower" } else { "[Shift-N] Wider" });
+				self.show_feedback(s);
+			}
+			Command::RampCycle => {
+				self.views.get().asciifier_m().cycle_ramp(1);
+				let s = format!("[K] Char ramp offset {}", self.views.get().asciifier().ramp_offset());
+				self.show_feedback(s);
+			}
+			Command::RampAuto => {
+				self.is_ramp_cycling = ! self.is_ramp_cycling;
+				let s = if self.is_ramp_cycling { "[Shift-K] Char ramp cycling on" } else { "[Shift-K] Char ramp cycling off" };
+				self.show_feedback(s.to_string());
+			}
+			Command::Charset(steps) => {
+				self.start_transition(None);
+				self.charsets.cycle(steps);
+				self.apply_charset();
+				let s = format!("[W] Charset: {}  {}", self.charsets.get().name, self.charsets.get().chars);
+				self.show_feedback(s);
+			}
+			Command::Dither => {
+				// (the choice is per render mode, and shared by all the views)
+				let dither = self.views.get().asciifier().dither().next();
+				let mapping = self.views.get().specs().mapping();
+				for i in 0..self.views.vec.len() {
+					self.views.get_num(i).asciifier_m().set_dither(mapping, dither);
+					self.views.get_num(i).dirty_exposure_checker().force_dirty();
+				}
+				self.show_feedback(format!("[Shift-D] Dithering: {}", dither.name()));
+			}
+			Command::Undo => {
+				let current = self.undo_state();
+				match self.undo_stack.undo(current) {
+					Some(state) => {
+						self.restore_undo_state(&state);
+						self.show_feedback(format!("[U] Undo ({} more)", self.undo_stack.undo_len()));
+					},
+					None => self.show_feedback("[U] Nothing to undo".to_string())
+				}
+			}
+			Command::Redo => {
+				let current = self.undo_state();
+				match self.undo_stack.redo(current) {
+					Some(state) => {
+						self.restore_undo_state(&state);
+						self.show_feedback(format!("[Shift-U] Redo ({} more)", self.undo_stack.redo_len()));
+					},
+					None => self.show_feedback("[Shift-U] Nothing to redo".to_string())
+				}
+			}
+			Command::HistoryBack => {
+				self.step_history(-1);
+			}
+			Command::HistoryForward => {
+				self.step_history(1);
+			}
+			Command::Transition => {
+				self.transition_style = self.transition_style.next();
+				self.show_feedback(format!("[~] Transition: {}", self.transition_style.name()));
+			}
+			Command::Edges => {
+				let b = ! self.views.get().asciifier().use_edges();
+				self.views.get().asciifier_m().set_use_edges(b);
+				self.views.get().dirty_exposure_checker().force_dirty();
+				let s = if b { "[Q] Edge glyphs on" } else { "[Q] Edge glyphs off" };
+				self.show_feedback(s.to_string());
+			}
+			Command::ToneCurve => {
+				let curve = self.views.get().asciifier().tone_curve().next(&self.piecewise_curve);
+				self.set_tone_curve(curve);
+			}
+			Command::ToneParam(steps) => {
+				let mut curve = self.views.get().asciifier().tone_curve().clone();
+				if curve.adjust(steps) {
+					self.set_tone_curve(curve);
+				} else {
+					self.show_feedback(format!("[< >] Tone curve {} has no parameter", curve.label()));
+				}
+			}
+			Command::Progressive => {
+				let b = ! self.views.get().progressive().enabled;
+				for i in 0..self.views.vec.len() {
+					self.views.get_num(i).progressive_m().enabled = b;
+				}
+				let s = if b {
+					"[P] Progressive refinement on"
+				} else {
+					"[P] Progressive refinement off"
+				};
+				self.show_feedback(s.to_string());
+			}
+			Command::MarianiSilver => {
+				let b = ! self.views.get().specs().use_mariani_silver;
+				for i in 0..self.views.vec.len() {
+					self.views.get_num(i).specs_m().use_mariani_silver = b;
+					self.views.get_num(i).dirty_fractal_checker().force_dirty();
+				}
+				let s = if b {
+					"[M] Mariani-Silver fill on"
+				} else {
+					"[M] Mariani-Silver fill off"
+				};
+				self.show_feedback(s.to_string());
+			}
+			Command::Supersample => {
+				let mut ss = self.views.get().specs().supersample;
+				ss.size = if ss.size >= constants::MAX_SUPERSAMPLE_SIZE { 1 } else { ss.size + 1 };
+				self.set_supersample(ss);
+				let s = if ss.is_on() {
+					format!("[X] Supersampling {}x{}", ss.size, ss.size)
+				} else {
+					"[X] Supersampling off".to_string()
+				};
+				self.show_feedback(s);
+			}
+			Command::SampleReduce => {
+				let mut ss = self.views.get().specs().supersample;
+				ss.reduce = match ss.reduce {
+					SampleReduce::Average => SampleReduce::Max,
+					SampleReduce::Max => SampleReduce::Min,
+					SampleReduce::Min => SampleReduce::Average,
+				};
+				self.set_supersample(ss);
+				let s = match ss.reduce {
+					SampleReduce::Average => "[Shift-X] Supersample reduction: average",
+					SampleReduce::Max => "[Shift-X] Supersample reduction: max",
+					SampleReduce::Min => "[Shift-X] Supersample reduction: min",
+				};
+				self.show_feedback(s.to_string());
+			}
+			Command::SamplePattern => {
+				let mut ss = self.views.get().specs().supersample;
+				ss.pattern = match ss.pattern {
+					SamplePattern::Grid => SamplePattern::Jitter,
+					SamplePattern::Jitter => SamplePattern::Grid,
+				};
+				self.set_supersample(ss);
+				let s = match ss.pattern {
+					SamplePattern::Grid => "[J] Supersample pattern: grid",
+					SamplePattern::Jitter => "[J] Supersample pattern: jittered",
+				};
+				self.show_feedback(s.to_string());
+			}
+			Command::RenderMode => {
+				let mode = match self.views.get().specs().render_mode {
+					RenderMode::Escape => RenderMode::Distance,
+					RenderMode::Distance => RenderMode::DistanceBand,
+					RenderMode::DistanceBand => RenderMode::Escape,
+				};
+				for i in 0..self.views.vec.len() {
+					self.views.get_num(i).specs_m().render_mode = mode;
+					self.views.get_num(i).dirty_fractal_checker().force_dirty();
+				}
+				let mut s = match mode {
+					RenderMode::Escape => "[D] Render mode: escape time",
+					RenderMode::Distance => "[D] Render mode: distance estimate",
+					RenderMode::DistanceBand => "[D] Render mode: distance estimate + escape time band",
+				}.to_string();
+				if ! self.views.get().specs().supports_render_options() {
+					s = s + " (n/a for this fractal)";
+				}
+				self.show_feedback(s);
+			}
+			Command::Coloring => {
+				self.start_transition(None);
+				let coloring = self.views.get().specs().coloring.next();
+				for i in 0..self.views.vec.len() {
+					self.views.get_num(i).specs_m().coloring = coloring;
+					self.views.get_num(i).dirty_fractal_checker().force_dirty();
+				}
+				let mut s = format!("[C] Coloring: {}", coloring.name());
+				if ! self.views.get().specs().supports_render_options() {
+					s = s + " (n/a for this fractal)";
+				} else if self.views.get().specs().uses_distance() {
+					s = s + " (n/a in distance mode)";
+				}
+				self.show_feedback(s);
+			}
+			Command::Upscale => {
+				let upscale = match self.views.get().progressive().upscale {
+					Upscale::Nearest => Upscale::Bilinear,
+					Upscale::Bilinear => Upscale::Nearest,
+				};
+				for i in 0..self.views.vec.len() {
+					self.views.get_num(i).progressive_m().upscale = upscale;
+				}
+				let s = match upscale {
+					Upscale::Nearest => "[Shift-P] Progressive upscaling: nearest",
+					Upscale::Bilinear => "[Shift-P] Progressive upscaling: bilinear",
+				};
+				self.show_feedback(s.to_string());
+			}
 			
 			Command::Size(w, h) => {
 				self.set_size(w, h);
 			},
+			Command::Calibrate => {
+				self.stop_view_anims();
+				self.is_calibrating = true;
+			},
 			
 			Command::Help => {
 				if self.help_anim.value > 0.0 {
@@ -208,28 +667,576 @@ impl<'a> App<'a> {
 				}
 			},
 			
+			Command::SplitView => {
+				self.toggle_split_view();
+			},
+			Command::CopyLocation => {
+				self.copy_location();
+			},
+			Command::Hud => {
+				self.is_hud_on = ! self.is_hud_on;
+				let s = if self.is_hud_on { "[I] HUD on" } else { "[I] HUD off" };
+				self.show_feedback(s.to_string());
+			},
+			Command::Minimap => {
+				self.minimap.enabled = ! self.minimap.enabled;
+				let s = if self.minimap.enabled { "[O] Overview on" } else { "[O] Overview off" };
+				self.show_feedback(s.to_string());
+			},
+
+			Command::ChangeFractalSet if self.views.panes.is_some() => {
+				// switches focus between the panes
+				let (left, right) = SPLIT_PANES;
+				self.views.index = if self.views.index == left { right } else { left };
+				let s = if self.views.index == left { "[F] Focus: Mandelbrot pane" } else { "[F] Focus: Julia pane" };
+				self.show_feedback(s.to_string());
+			},
 			Command::ChangeFractalSet => {
+				self.undo_stack.push(undo_state);
 				self.stop_view_anims();
-				self.interview_last_index = self.views.index;
-				self.views.index += 1;
-				if self.views.index >= self.views.vec.len() {
-					self.views.index = 0;
-				}
-				self.interview_animator.value = 0.0;
-				self.interview_animator.set_anim(
-						Anim::Velocity { velocity: 1.0/20.0, friction: 1.0, epsilon: None });
+				let index = (self.views.index + 1) % self.views.vec.len();
+				let target = self.view_position(index);
+				self.start_transition(Some(target));
+				self.views.index = index;
 				
 				let s = match self.views.get().specs().fractal_type {
-					FractalType::Mandelbrot => "[F] Fractal type: Mandelbrot",
-					FractalType::Julia(..) => "[F] Fractal type: Julia",
+					FractalType::Mandelbrot => "[F] Fractal type: Mandelbrot".to_string(),
+					FractalType::Julia(..) => "[F] Fractal type: Julia".to_string(),
+					FractalType::Newton(p) => format!("[F] Fractal type: Newton ({})", p),
+					FractalType::Buddhabrot(mode) => format!("[F] Fractal type: {}", mode.name()),
+					FractalType::Lyapunov(sequence) => format!("[F] Fractal type: Lyapunov ({})", sequence),
+					FractalType::PointCloud(system) => format!("[F] Fractal type: {}", system),
 				};
-				self.show_feedback(s.to_string()); 
+				self.show_feedback(s); 
 				
 			}
 			_ => {}
 		}
 	}
 
+	/**
+	 * While calibrating, left/right (and, more finely, up/down) adjusts the character aspect ratio, 
+	 * which gets saved to the config file when done 
+	 */
+	fn handle_calibration_command(&mut self, command: &Command) {
+		match *command {
+			Command::PositionVelocity(xm, ym) => {
+				let ar = self.views.get().specs().element_ar;
+				let ratio = 1.0 + xm * constants::ASPECT_RATIO_INCREMENT - ym * constants::ASPECT_RATIO_FINE_INCREMENT;
+				self.set_element_ar(ar * ratio);
+			},
+			Command::Size(w, h) => {
+				self.set_size(w, h);
+			},
+			Command::Calibrate | Command::Help | Command::Stop => {
+				self.is_calibrating = false;
+				let ar = self.views.get().specs().element_ar;
+				let s = match self.config.save_value("aspect", &format!("{:.4}", ar)) {
+					Ok(_) => format!("Character aspect ratio {:.4} saved", ar),
+					Err(e) => format!("Character aspect ratio {:.4} (couldn't save: {})", ar, e),
+				};
+				self.show_feedback(s);
+			},
+			_ => {}
+		}
+	}
+
+	/**
+	 * The prompt is for entering either a custom iteration formula or a location to go to
+	 */
+	fn handle_prompt_command(&mut self, command: &Command) {
+		match *command {
+			Command::PromptOpen(prompt) => {
+				let text = match prompt {
+					Prompt::Formula => self.formula_text.clone(),
+					Prompt::Location => "".to_string(),
+				};
+				self.prompt = Some((prompt, text));
+			},
+			Command::PromptChar(c) => {
+				match self.prompt {
+					Some((_, ref mut s)) => s.push(c),
+					None => {}
+				}
+			},
+			Command::PromptBackspace => {
+				match self.prompt {
+					Some((_, ref mut s)) => { s.pop(); },
+					None => {}
+				}
+			},
+			Command::PromptEnter => {
+				match self.prompt.take() {
+					Some((Prompt::Formula, s)) => self.apply_formula(&s),
+					Some((Prompt::Location, s)) => self.goto_location(&s),
+					None => {}
+				}
+			},
+			Command::PromptCancel => {
+				self.prompt = None;
+			},
+			_ => {}
+		}
+	}
+
+	/**
+	 * Sets the iteration formula of the mandelbrot and julia views (empty string for the built-in one),
+	 * or shows the parse error
+	 */
+	fn apply_formula(&mut self, text: &str) {
+		let text = text.trim();
+		let formula = if text.len() == 0 {
+			None
+		} else {
+			match Formula::parse(text) {
+				Ok(f) => Some(f),
+				Err(e) => {
+					self.show_feedback(format!("[:] Formula error: {}", e));
+					return;
+				}
+			}
+		};
+		for i in 0..self.views.vec.len() {
+			let applies = match self.views.get_num(i).specs().fractal_type {
+				FractalType::Mandelbrot | FractalType::Julia(..) => true,
+				_ => false
+			};
+			if applies {
+				self.views.get_num(i).specs_m().formula = formula;
+				self.views.get_num(i).dirty_fractal_checker().force_dirty();
+			}
+		}
+		self.formula_text = text.to_string();
+		let s = match formula {
+			Some(_) => format!("[:] Formula: {}", text),
+			None => "[:] Formula: z^2 + c (built-in)".to_string(),
+		};
+		self.show_feedback(s);
+	}
+
+	/**
+	 * Sets every view to use the current charset
+	 */
+	fn apply_charset(&mut self) {
+		let chars = self.charsets.get().chars.clone();
+		for i in 0..self.views.vec.len() {
+			let view = self.views.get_num(i);
+			view.asciifier_m().set_chars(&chars);
+			view.calc_index_matrix();
+		}
+	}
+
+	fn exposure_lock_info(&mut self, prefix: &str) -> String {
+		match self.views.get().exposure_lock() {
+			Some((f, c)) => format!("{} (exposure locked at {:.0}-{:.0})", prefix, f, c),
+			None => prefix.to_string()
+		}
+	}
+
+	/**
+	 * Sets the current view's tone curve, with feedback
+	 */
+	fn set_tone_curve(&mut self, curve: ToneCurve) {
+		let s = if self.views.get().specs().mapping() != Mapping::Bias {
+			format!("[T] Tone curve: {} (n/a for this render mode)", curve.label())
+		} else if self.views.get().is_equalizing() {
+			format!("[T] Tone curve: {} (n/a while equalizing)", curve.label())
+		} else {
+			format!("[T] Tone curve: {}", curve.label())
+		};
+		self.views.get().asciifier_m().set_tone_curve(curve);
+		self.views.get().dirty_exposure_checker().force_dirty();
+		self.show_feedback(s);
+		self.save_tone_curve();
+	}
+
+	/**
+	 * Remembers the current view's tone curve between sessions (see `HistoryFile`)
+	 */
+	fn save_tone_curve(&mut self) {
+		let index = self.views.index;
+		let curve = self.views.get().asciifier().tone_curve().clone();
+		if self.histories[index].tone_curve.as_ref() != Some(&curve) {
+			self.histories[index].tone_curve = Some(curve);
+			self.save_history();
+		}
+	}
+
+	/**
+	 * Snapshots the current frame, which then gets transitioned away from (see `update_transition()` and `draw()`).
+	 * Should be called just before the change.
+	 * 
+	 * target - where the view will be afterwards, which zoom-through needs 
+	 * (without it, zoom-through falls back to crossfade)
+	 */
+	fn start_transition(&mut self, target: Option<ViewPosition>) {
+		// (one that's underway gets finished first)
+		self.finish_transition();
+		if self.views.panes.is_some() {
+			return;
+		}
+		let style = match (self.transition_style, target) {
+			(TransitionStyle::ZoomThrough, None) => TransitionStyle::Crossfade,
+			(style, _) => style
+		};
+		let mut transition = {
+			let view = self.views.get_im();
+			Transition::new(style, view.asciifier(), view.index_matrix(), view.family_matrix(), view.edge_matrix(), self.count as usize)
+		};
+		if style == TransitionStyle::ZoomThrough {
+			let from = self.view_position(self.views.index);
+			transition.zoom = match target {
+				Some(to) => Some((from, to)),
+				None => None
+			};
+		}
+		self.transition = Some(transition);
+	}
+
+	/**
+	 * Zoom-through: zooms the outgoing view out during the first half, 
+	 * and the incoming view in during the second half
+	 */
+	fn update_transition(&mut self) {
+		let (is_complete, zoom) = match self.transition {
+			Some(ref t) => (t.is_complete(), match t.zoom {
+				Some((from, to)) => Some((from, to, t.progress(), t.zoom_multiplier())),
+				None => None
+			}),
+			None => return
+		};
+		if is_complete {
+			self.finish_transition();
+			return;
+		}
+		match zoom {
+			Some((from, to, progress, multiplier)) => {
+				if progress < 0.5 {
+					self.views.index = from.index;
+					self.set_view_position(&from, multiplier);
+				} else {
+					if from.index != to.index {
+						self.set_view_position(&from, 1.0);
+					}
+					self.views.index = to.index;
+					self.set_view_position(&to, multiplier);
+				}
+			},
+			None => {}
+		}
+	}
+
+	/**
+	 * Ends the transition, if any, leaving the views where they would have been at the end of it
+	 */
+	fn finish_transition(&mut self) {
+		let transition = match self.transition.take() {
+			Some(t) => t,
+			None => return
+		};
+		match transition.zoom {
+			Some((from, to)) => {
+				self.set_view_position(&from, 1.0);
+				self.set_view_position(&to, 1.0);
+				self.views.index = to.index;
+			},
+			None => {}
+		}
+	}
+
+	fn view_position(&mut self, index: usize) -> ViewPosition {
+		let view = self.views.get_num(index);
+		ViewPosition {
+			index: index,
+			center: view.position_animator().value,
+			width: view.width_animator().value,
+			rotation: view.rotation_animator().value,
+		}
+	}
+
+	/**
+	 * Places the view directly (stopping any animation)
+	 * width_multiplier - used by zoom-through
+	 */
+	fn set_view_position(&mut self, position: &ViewPosition, width_multiplier: f64) {
+		let view = self.views.get_num(position.index);
+		view.position_animator().set_anim(Anim::None);
+		view.position_animator().value = position.center;
+		view.width_animator().set_anim(Anim::None);
+		view.width_animator().value = position.width * width_multiplier;
+		view.rotation_animator().set_anim(Anim::None);
+		view.rotation_animator().value = position.rotation;
+	}
+
+	fn view_ids(&self) -> Vec<&'static str> {
+		(0..self.views.vec.len()).map(|i| self.views.get_num_im(i).specs().fractal_type.id()).collect()
+	}
+
+	fn camera_state(&mut self) -> CameraState {
+		let view = self.views.get();
+		CameraState {
+			center: view.position_animator().value,
+			width: view.width_animator().value,
+			rotation: view.rotation_animator().value,
+		}
+	}
+
+	/**
+	 * Where the focused view's camera is tweening to, if its position, width and rotation all have targets
+	 * (as they do after a history step or an undo)
+	 */
+	fn camera_target(&mut self) -> Option<CameraState> {
+		let view = self.views.get();
+		let center = match *view.position_animator().anim() {
+			Anim::Target { target, .. } => target,
+			_ => return None
+		};
+		let width = match *view.width_animator().anim() {
+			Anim::Target { target, .. } => target,
+			_ => return None
+		};
+		let rotation = match *view.rotation_animator().anim() {
+			Anim::Target { target, .. } => target,
+			_ => return None
+		};
+		Some(CameraState { center: center, width: width, rotation: rotation })
+	}
+
+	/**
+	 * Records the focused view's camera into its history once it has stayed still for a while
+	 * (which is what happens at the end of a click-tween or a coord anim, or when the user stops moving)
+	 */
+	fn update_history(&mut self) {
+		if self.transition.is_some() {
+			self.last_camera = None;
+			return;
+		}
+		let state = self.camera_state();
+		let is_still = match self.last_camera {
+			Some(last) => last.is_near(&state, history::STILL_RATIO),
+			None => false
+		};
+		self.still_frames = if is_still { self.still_frames + 1 } else { 0 };
+		self.last_camera = Some(state);
+
+		if self.still_frames == history::PAUSE_FRAMES {
+			let index = self.views.index;
+			if self.histories[index].record(state) {
+				self.save_history();
+			}
+		}
+	}
+
+	/**
+	 * Tweens the focused view to the previous (steps < 0) or next state in its history
+	 */
+	fn step_history(&mut self, steps: i32) {
+		let index = self.views.index;
+		let state = if steps < 0 {
+			// (the current state gets recorded first, if it hasn't been, so that it can be gone forward to;
+			// but not while still tweening to the current history state, which would drop the states ahead of it)
+			let is_heading_to_current = match (self.camera_target(), self.histories[index].current()) {
+				(Some(target), Some(current)) => target.is_near(&current, history::STILL_RATIO),
+				_ => false
+			};
+			if ! is_heading_to_current {
+				let current = self.camera_state();
+				self.histories[index].record(current);
+			}
+			self.histories[index].back()
+		} else {
+			self.histories[index].forward()
+		};
+		let state = match state {
+			Some(s) => s,
+			None => {
+				let s = if steps < 0 { "[(] Already at the start of this view's history" } else { "[)] Already at the end of this view's history" };
+				self.show_feedback(s.to_string());
+				return;
+			}
+		};
+
+		let undo_state = self.undo_state();
+		self.undo_stack.push(undo_state);
+		self.stop_view_anims();
+		self.views.get().position_animator().set_anim(
+				Anim::Target { target: state.center, coefficient: constants::TARGET_COEF, epsilon: None });
+		self.views.get().width_animator().set_anim(
+				Anim::Target { target: state.width, coefficient: constants::TARGET_COEF, epsilon: None });
+		self.views.get().rotation_animator().set_anim(
+				Anim::Target { target: state.rotation, coefficient: constants::TARGET_COEF, epsilon: None });
+		self.still_frames = 0;
+		self.save_history();
+
+		let (n, len) = self.histories[index].position();
+		let key = if steps < 0 { "(" } else { ")" };
+		self.show_feedback(format!("[{}] History {} of {}", key, n, len));
+	}
+
+	fn undo_state(&mut self) -> UndoState {
+		let view_index = self.views.index;
+		let view = self.views.get();
+		UndoState {
+			view_index: view_index,
+			center: view.position_animator().value,
+			width: view.width_animator().value,
+			rotation: view.rotation_animator().value,
+			julia_c: match view.specs().fractal_type {
+				FractalType::Julia(c) => Some(c),
+				_ => None
+			},
+			use_exposure: view.use_exposure(),
+			exposure_mode: view.exposure_mode(),
+		}
+	}
+
+	/**
+	 * Switches back to the state's view if need be, and tweens back to its camera and julia seed
+	 */
+	fn restore_undo_state(&mut self, state: &UndoState) {
+		if state.view_index != self.views.index {
+			match self.views.panes {
+				Some((left, right)) if state.view_index != left && state.view_index != right => self.toggle_split_view(),
+				_ => {}
+			}
+			self.stop_view_anims();
+			let target = ViewPosition { index: state.view_index, center: state.center, width: state.width, rotation: state.rotation };
+			self.start_transition(Some(target));
+			self.views.index = state.view_index;
+		}
+		self.stop_view_anims();
+
+		let view = self.views.get();
+		view.position_animator().set_anim(
+				Anim::Target { target: state.center, coefficient: constants::TARGET_COEF, epsilon: None });
+		view.width_animator().set_anim(
+				Anim::Target { target: state.width, coefficient: constants::TARGET_COEF, epsilon: None });
+		view.rotation_animator().set_anim(
+				Anim::Target { target: state.rotation, coefficient: constants::TARGET_COEF, epsilon: None });
+		match state.julia_c {
+			Some(c) => view.tween_julia_c(c),
+			None => {}
+		}
+		if view.use_exposure() != state.use_exposure {
+			view.toggle_use_exposure();
+			view.set_exposure_lock(None);
+		}
+		view.set_exposure_mode(state.exposure_mode);
+	}
+
+	fn save_history(&mut self) {
+		let ids = self.view_ids();
+		match HistoryFile::save(&ids, &self.histories) {
+			Err(e) => self.show_feedback(format!("Couldn't save history: {}", e)),
+			_ => {}
+		}
+	}
+
+	fn get_location(&mut self) -> Location {
+		Location {
+			type_id: self.views.get().specs().fractal_type.id().to_string(),
+			center: self.views.get().position_animator().value,
+			width: self.views.get().width_animator().value,
+			rotation: self.views.get().rotation_animator().value,
+			max_val: self.views.get().specs().max_val,
+			julia_c: match self.views.get().specs().fractal_type {
+				FractalType::Julia(c) => Some(c),
+				_ => None
+			},
+			tone_curve: self.views.get().asciifier().tone_curve().clone(),
+		}
+	}
+
+	/**
+	 * Puts the current view's location on the clipboard, using the OSC 52 escape sequence
+	 */
+	fn copy_location(&mut self) {
+		let s = self.get_location().to_string();
+		print!("{}", ansi::copy_to_clipboard(&s));
+		self.show_feedback(format!("[Y] Copied: {}", s));
+	}
+
+	/**
+	 * Switches to the first view of the location's fractal type (leaving split view if it isn't one of the panes), 
+	 * and animates to the location, or shows the parse error
+	 */
+	fn goto_location(&mut self, text: &str) {
+		let location = match Location::parse(text) {
+			Ok(l) => l,
+			Err(e) => {
+				self.show_feedback(format!("[G] Location error: {}", e));
+				return;
+			}
+		};
+		let index = match (0..self.views.vec.len()).find(|&i| self.views.get_num_im(i).specs().fractal_type.id() == location.type_id) {
+			Some(i) => i,
+			None => {
+				self.show_feedback(format!("[G] Location error: Unknown type: {}", location.type_id));
+				return;
+			}
+		};
+		let state = self.undo_state();
+		self.undo_stack.push(state);
+
+		if self.views.panes.is_some() && index != SPLIT_PANES.0 && index != SPLIT_PANES.1 {
+			self.toggle_split_view();
+		}
+		let target = ViewPosition { index: index, center: location.center, width: location.width, rotation: location.rotation };
+		self.start_transition(Some(target));
+		if index != self.views.index {
+			self.stop_view_anims();
+			self.views.index = index;
+		}
+		self.stop_view_anims();
+
+		// (`max_val` is only an iteration count for the escape-time types)
+		let view = self.views.get();
+		let uses_iter = match view.specs().fractal_type {
+			FractalType::Mandelbrot | FractalType::Julia(..) | FractalType::Newton(..) => true,
+			_ => false
+		};
+		if uses_iter && view.specs().max_val != location.max_val {
+			view.specs_m().max_val = location.max_val;
+			// (clears the matrix, whose values may now be out of range)
+			let w = view.fractal_matrix().width();
+			let h = view.fractal_matrix().height();
+			view.set_matrix_size(w, h);
+		}
+		match (view.specs().fractal_type, location.julia_c) {
+			(FractalType::Julia(_), Some(c)) => view.specs_m().fractal_type = FractalType::Julia(c),
+			_ => {}
+		}
+		view.asciifier_m().set_tone_curve(location.tone_curve);
+		view.dirty_exposure_checker().force_dirty();
+		view.dirty_fractal_checker().force_dirty();
+
+		if self.transition.is_some() {
+			// jumps there, behind the transition
+			self.set_view_position(&target, 1.0);
+		} else {
+			let view = self.views.get();
+			view.position_animator().set_anim(
+					Anim::Target { target: location.center, coefficient: constants::TARGET_COEF, epsilon: None });
+			view.width_animator().set_anim(
+					Anim::Target { target: location.width, coefficient: constants::TARGET_COEF, epsilon: None });
+			view.rotation_animator().set_anim(
+					Anim::Target { target: location.rotation, coefficient: constants::TARGET_COEF, epsilon: None });
+		}
+		self.save_tone_curve();
+
+		self.show_feedback("[G] Going to location".to_string());
+	}
+
+	/**
+	 * Updates the character aspect ratio of all views
+	 */
+	fn set_element_ar(&mut self, ar: f64) {
+		for i in 0..self.views.vec.len() {
+			self.views.get_num(i).specs_m().element_ar = ar;
+			self.views.get_num(i).dirty_fractal_checker().force_dirty();
+		}
+	}
+
 	fn stop_view_anims(&mut self) {
 		self.views.get().position_animator().set_anim( Anim::None );
 		self.views.get().width_animator().set_anim( Anim::None ); 
@@ -237,34 +1244,70 @@ impl<'a> App<'a> {
 		self.views.get().stop_coord_anim();;
 	}
 
+	/**
+	 * Applies supersample settings to all views
+	 */
+	fn set_supersample(&mut self, ss: Supersample) {
+		for i in 0..self.views.vec.len() {
+			self.views.get_num(i).specs_m().supersample = ss;
+			self.views.get_num(i).dirty_fractal_checker().force_dirty();
+		}
+	}
+
 	pub fn update(&mut self) {
 		
-		self.views.get().update();
+		match self.views.panes {
+			Some((left, right)) => {
+				self.views.get_num(left).update();
+				self.views.get_num(right).update();
+				self.link_julia_seed();
+			},
+			None => self.views.get().update()
+		}
 		
-		self.interview_animator.update();
-		if self.interview_animator.value >= 1.0 {
-			self.interview_animator.value = 0.0;
-			self.interview_animator.set_anim(Anim::None);
+		if self.is_ramp_cycling && self.count % RAMP_CYCLE_FRAMES == 0 {
+			self.views.get().asciifier_m().cycle_ramp(1);
 		}
 		
+		self.update_transition();
+		self.update_history();
+		
 		self.help_anim.update();		
 	}
 	
-	pub fn calculate(&mut self) {
-        self.views.get().calculate();
+	pub fn calculate(&mut self, budget_usec: i64) -> CalcStats {
+		match self.views.panes {
+			Some((left, right)) => {
+				// (the budget gets split between the panes)
+				let mut stats = self.views.get_num(left).calculate(budget_usec / 2);
+				stats.add(&self.views.get_num(right).calculate(budget_usec / 2));
+				stats
+			},
+			None => self.views.get().calculate(budget_usec)
+		}
 	}
 	
-	pub fn draw(&mut self, debug_info: &String) {
+	/**
+	 * fps - the measured frame rate, for the HUD
+	 */
+	pub fn draw(&mut self, debug_info: &String, fps: f64) {
 		
-		let should_crossfade = match self.interview_animator.anim() { &Anim::None => false, _ => true };
-		if should_crossfade {
-			Matrix::interpolate(self.interview_animator.value,
-					&self.views.get_num_im(self.interview_last_index).index_matrix(), 
-					&self.views.get_im().index_matrix(), 
-					&mut self.interview_matrix);
-       		self.views.get_im().asciifier().write_textbuffer(&self.interview_matrix, &mut self.text_buffer.buffer);
+		let is_transitioning = match self.transition {
+			Some(ref t) => t.fits(self.views.get_im().index_matrix().width(), self.views.get_im().index_matrix().height()),
+			None => false
+		};
+		if self.views.panes.is_some() {
+			self.draw_panes();
+		} else if is_transitioning {
+			let view = self.views.get_im();
+			match self.transition {
+				Some(ref mut t) => t.write_textbuffer(view.asciifier(), view.index_matrix(), view.family_matrix(), 
+						view.edge_matrix(), view.specs().element_ar, &mut self.text_buffer.buffer),
+				None => {}
+			}
 		} else {
-       		self.views.get_im().asciifier().write_textbuffer(&self.views.get_im().index_matrix(), &mut self.text_buffer.buffer);
+       		self.views.get_im().asciifier().write_textbuffer_at(&self.views.get_im().index_matrix(), 
+       				&self.views.get_im().family_matrix(), Some(self.views.get_im().edge_matrix()), &mut self.text_buffer.buffer, 0);
 		}
 
         if constants::SHOW_DEBUG_TEXT {
@@ -272,29 +1315,78 @@ impl<'a> App<'a> {
 	        self.text_buffer.draw_string(&self.views.get_im().debug(), 1,2);
         }
 
-        if self.count % 60 < 10 {  // show center-point
-        	let x =  (self.view_width / 2) as i32;
+        if self.count % 60 < 10 {  // show center-point (of the focused pane, if split)
+        	let (pane_x, pane_w) = self.focused_pane_rect();
+        	let x =  (pane_x + pane_w / 2) as i32;
         	let y = (self.view_height / 2) as i32;
 	        self.text_buffer.draw_string(&"█".to_string(), x,y);	        	
         }
         
+        if self.minimap.enabled {
+        	let (pane_x, _) = self.focused_pane_rect();
+        	let index = self.views.index;
+        	let specs = self.views.get().specs().clone();
+        	let center = self.views.get().position_animator().value;
+        	let width = self.views.get().width_animator().value;
+        	let rotation = self.views.get().rotation_animator().value;
+        	let view_w = self.views.get().fractal_matrix().width();
+        	let view_h = self.views.get().fractal_matrix().height();
+        	let charset = self.charsets.get().chars.clone();
+        	self.minimap.draw(&mut self.text_buffer, pane_x, index, &charset, &specs, center, width, rotation, view_w, view_h);
+        }
+        
+        // (the HUD pushes the other overlays away from the edge it's on)
+        let (hud_rows_top, hud_rows_bottom) = if self.is_hud_on {
+        	let lines = self.get_hud_lines(fps);
+        	let n = lines.len();
+        	match self.config.hud_position {
+        		HudPosition::Top => {
+        			self.text_buffer.draw_text_block(&lines, 0, 0);
+        			(n, 0)
+        		},
+        		HudPosition::Bottom => {
+        			self.text_buffer.draw_text_block(&lines, 0, self.view_height as i32 - n as i32);
+        			(0, n)
+        		}
+        	}
+        } else {
+        	(0, 0)
+        };
+        
         if self.help_anim.value <= 1.0 {
         	let z = self.get_zoom();
         	let c = match self.views.get().specs().fractal_type {  
-        		FractalType::Mandelbrot => None,
         		FractalType::Julia(c) => Some(c),
+        		_ => None,
         	};
         	self.text_buffer.draw_help_dialog(self.help_anim.value, &self.views.get().position_animator().value, z,  c);
         }
         
 		if ! self.has_shown_help {
         	let s = " [H] help ".to_string();
-        	self.text_buffer.draw_string(&s, (self.view_width - s.len() - 1) as i32, 1);
+        	self.text_buffer.draw_string(&s, (self.view_width - s.len() - 1) as i32, 1 + hud_rows_top as i32);
 		}
 		
-		if self.feedback_countdown > 0 {
-			self.feedback_countdown -= 1;
-			self.text_buffer.draw_string(&self.feedback_string, 1, (self.view_height - 2) as i32);
+		if self.is_calibrating {
+			self.draw_calibration();
+		}
+		
+		let feedback_y = self.view_height as i32 - 2 - hud_rows_bottom as i32;
+		match self.prompt {
+			Some((prompt, ref s)) => {
+				let label = match prompt {
+					Prompt::Formula => "Formula (z, c)",
+					Prompt::Location => "Go to location",
+				};
+				let line = format!(" {}: {}_ ", label, s);
+				self.text_buffer.draw_string(&line, 1, feedback_y);
+			},
+			None => {
+				if self.feedback_countdown > 0 {
+					self.feedback_countdown -= 1;
+					self.text_buffer.draw_string(&self.feedback_string, 1, feedback_y);
+				}
+			}
 		}
    
         self.text_buffer.print();
@@ -302,6 +1394,10 @@ impl<'a> App<'a> {
         self.count += 1;
 	}
 	
+	pub fn config_warnings(&self) -> &Vec<String> {
+		&self.config.warnings
+	}
+
 	pub fn show_feedback(&mut self, string: String) {
 		self.feedback_string = format!(" {} ", string);
 		self.feedback_countdown = 60;
@@ -310,13 +1406,141 @@ impl<'a> App<'a> {
 	fn set_size(&mut self, w: usize, h: usize) {
 		self.view_width = w;
 		self.view_height = h;
+		let (left_w, right_w) = self.pane_widths();
 		for i in 0..self.views.vec.len() {
-			(*self.views.vec[i]).set_matrix_size(w, h);
+			let pane_w = match self.views.panes {
+				Some((left, _)) if i == left => left_w,
+				Some((_, right)) if i == right => right_w,
+				_ => w
+			};
+			(*self.views.vec[i]).set_matrix_size(pane_w, h);
 		}
 		self.text_buffer.set_size(self.view_width, self.view_height);
-		self.interview_matrix = Matrix::new(self.view_width, self.view_height);
+		self.finish_transition();
 	}
 	
+	/**
+	 * Split view: the widths of the left and right panes, which are separated by a one-character divider
+	 * (each pane is at least one column, even if that doesn't fit a very narrow terminal)
+	 */
+	fn pane_widths(&self) -> (usize, usize) {
+		let left_w = cmp::max(self.view_width.saturating_sub(1) / 2, 1);
+		let right_w = cmp::max(self.view_width.saturating_sub(1 + left_w), 1);
+		(left_w, right_w)
+	}
+
+	/**
+	 * Returns the column and width of the focused pane (which is the whole screen, when not split)
+	 */
+	fn focused_pane_rect(&self) -> (usize, usize) {
+		match self.views.panes {
+			Some((_, right)) => {
+				let (left_w, right_w) = self.pane_widths();
+				if self.views.index == right { (left_w + 1, right_w) } else { (0, left_w) }
+			},
+			None => (0, self.view_width)
+		}
+	}
+
+	fn toggle_split_view(&mut self) {
+		if self.views.panes.is_some() {
+			self.views.panes = None;
+			self.linked_center = None;
+			self.show_feedback("[V] Split view off".to_string());
+		} else {
+			let (left, right) = SPLIT_PANES;
+			if self.views.index != left && self.views.index != right {
+				self.views.index = left;
+			}
+			self.finish_transition();
+			self.views.panes = Some(SPLIT_PANES);
+			self.show_feedback("[V] Split view on (F switches pane)".to_string());
+		}
+		let w = self.view_width;
+		let h = self.view_height;
+		self.set_size(w, h);
+	}
+
+	/**
+	 * Split view: the julia pane's seed tracks the mandelbrot pane's center
+	 */
+	fn link_julia_seed(&mut self) {
+		let (left, right) = SPLIT_PANES;
+		let center = self.views.get_num(left).position_animator().value;
+		let is_changed = match self.linked_center {
+			Some(c) => c.x != center.x || c.y != center.y,
+			None => true
+		};
+		if ! is_changed {
+			return;
+		}
+		self.linked_center = Some(center);
+		let julia = self.views.get_num(right);
+		julia.stop_coord_anim();
+		julia.specs_m().fractal_type = FractalType::Julia(Complex64 { re: center.x, im: center.y });
+		julia.dirty_fractal_checker().force_dirty();
+	}
+
+	fn draw_panes(&mut self) {
+		let (left, right) = SPLIT_PANES;
+		let (left_w, _) = self.pane_widths();
+		{
+			let view = self.views.get_num_im(left);
+			view.asciifier().write_textbuffer_at(view.index_matrix(), view.family_matrix(), Some(view.edge_matrix()), &mut self.text_buffer.buffer, 0);
+		}
+		{
+			let view = self.views.get_num_im(right);
+			view.asciifier().write_textbuffer_at(view.index_matrix(), view.family_matrix(), Some(view.edge_matrix()), &mut self.text_buffer.buffer, left_w + 1);
+		}
+		if left_w < self.text_buffer.buffer.width() {
+			for y in 0..self.view_height {
+				self.text_buffer.buffer.set(left_w, y, '│');
+			}
+		}
+	}
+
+	fn draw_calibration(&mut self) {
+		let ar = self.views.get().specs().element_ar;
+		let radius = (self.view_height as f64 * 0.4).min(self.view_width as f64 * ar * 0.4);
+		self.text_buffer.clear();
+		self.text_buffer.draw_circle(ar, radius, '@');
+
+		let lines = [
+			" CALIBRATE CHARACTER ASPECT RATIO ".to_string(),
+			format!(" Current: {:.4} ", ar),
+			" Adjust until the circle looks round: ".to_string(),
+			" left/right | coarse ".to_string(),
+			"    up/down | fine ".to_string(),
+			"  shift + c | done (saves to config) ".to_string(),
+		];
+		for i in 0..lines.len() {
+			self.text_buffer.draw_string(&lines[i], 1, 1 + i as i32);
+		}
+	}
+
+	fn get_hud_lines(&mut self, fps: f64) -> Vec<String> {
+		let zoom = self.get_zoom();
+		let view_w = self.views.get().fractal_matrix().width();
+		let width = self.views.get().width_animator().value;
+		let info = HudInfo {
+			center: self.views.get().position_animator().value,
+			cell_size: width / view_w as f64,
+			zoom: zoom,
+			rotation: self.views.get().rotation_animator().value,
+			max_val: self.views.get().specs().max_val,
+			julia_c: match self.views.get().specs().fractal_type {
+				FractalType::Julia(c) => Some(c),
+				_ => None
+			},
+			exposure: (self.views.get().asciifier().floor(), self.views.get().asciifier().ceil(), 
+					self.views.get().asciifier().bias()),
+			is_exposure_locked: self.views.get().exposure_lock().is_some(),
+			is_equalizing: self.views.get().is_equalizing(),
+			fps: fps,
+		};
+		Hud::lines(&info, self.view_width)
+	}
+
 	fn get_zoom(&mut self) -> f64 {
 		let w2 = self.views.get().width_animator().value;
 		let w1 = self.views.get().specs().default_width;
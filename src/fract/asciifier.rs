@@ -1,14 +1,40 @@
 use std::cmp::{min, max};
-use leelib::math;
 use leelib::matrix::Matrix;
+use fract::tonecurve::ToneCurve;
+use fract::dither::Dither;
+use fract::edges::Edges;
 
 
 pub const CHARSET1: &'static str = " .,:;i1tfLCG08@";
 
-// calibrated vaguely for Monaco 12
+// calibrated vaguely for Monaco 12 (see `charset-calibrate` for making a ramp for another font)
 pub const CHARSET2: &'static str = " .,`'\"^:;-~=+*ixcnaeomlfh1IEUOQWX%#$&@";
+
+// character 'families', one per nonzero family value (see `CellValue`), eg per root basin of a newton fractal;
+// each ordered by weight like the main charset, but without a blank, so that every basin stays recognizable
+pub const FAMILY_CHARSETS: [&'static str; 8] = [ 
+	".:oO0@", "`-=+*#", ",;ixX%", "'^nmW&", "_~sS$8", "\"!lIH", "-<{[B", "*xzZM" ];
  
 
+// `Mapping::Lines`: ratios below this map to the lightest char 
+const LINES_EDGE: f64 = 0.35;
+
+
+/**
+ * How values (once normalized using floor and ceil) get mapped to char indices
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mapping {
+	// the asciifier's tone curve (which by default blends between a sqrt and a square curve, as per `bias`)
+	Bias,
+	// steep s-curve, so that only values near the ceiling show up, as thin lines (for distance estimates)
+	Lines,
+	// distance from the midpoint between floor and ceil, in either direction (the sign is expected to be 
+	// shown by the cell's family; see `FractalSpecs::is_signed()`)
+	Signed,
+}
+
+
 /**
  * 'Asciifies' values into chars.
  */
@@ -17,6 +43,11 @@ pub struct Asciifier {
     floor: f64,
     ceil: f64,
     bias: f64,
+    mapping: Mapping,
+    tone_curve: ToneCurve,
+    ramp_offset: usize,  // rotates the chars, like palette cycling
+    dithers: [Dither; 3],  // one per `Mapping`, so that each render mode keeps its own
+    use_edges: bool,  // directional glyphs on strong edges (see `Edges`)
     
     range: f64,
     step: f64,
@@ -29,6 +60,11 @@ impl Asciifier {
             floor: floor,
             ceil: ceil,
             bias: 0.0,
+            mapping: Mapping::Bias,
+            tone_curve: ToneCurve::Bias,
+            ramp_offset: 0,
+            dithers: [Dither::None; 3],
+            use_edges: false,
             
             range:0.0,
             step: 0.0,
@@ -80,6 +116,45 @@ impl Asciifier {
     	self.bias = bias;
     }
 
+    pub fn set_mapping(&mut self, mapping: Mapping) {
+    	self.mapping = mapping;
+    }
+
+    pub fn tone_curve(&self) -> &ToneCurve {
+    	&self.tone_curve
+    }
+    pub fn set_tone_curve(&mut self, tone_curve: ToneCurve) {
+    	self.tone_curve = tone_curve;
+    }
+
+	/**
+	 * The dithering used with the current mapping
+	 */
+    pub fn dither(&self) -> Dither {
+    	self.dithers[self.mapping as usize]
+    }
+    pub fn set_dither(&mut self, mapping: Mapping, dither: Dither) {
+    	self.dithers[mapping as usize] = dither;
+    }
+
+    pub fn use_edges(&self) -> bool {
+    	self.use_edges
+    }
+    pub fn set_use_edges(&mut self, b: bool) {
+    	self.use_edges = b;
+    }
+
+    pub fn ramp_offset(&self) -> usize {
+    	self.ramp_offset
+    }
+    /**
+     * Steps the offset forward or back, wrapping around the length of the charset
+     */
+    pub fn cycle_ramp(&mut self, steps: i32) {
+    	let n = self.chars.len() as i32;
+    	self.ramp_offset = (((self.ramp_offset as i32 + steps) % n + n) % n) as usize;
+    }
+
 	pub fn write_textbuffer_with_transform(&self, fractal_matrix: &Matrix<u16>, text_buffer: &mut Matrix<char>) {
 		let w = min(text_buffer.width(), fractal_matrix.width());
 		let h = min(text_buffer.height(), fractal_matrix.height());
@@ -91,19 +166,107 @@ impl Asciifier {
 		}		
 	}
 	
-	pub fn write_textbuffer(&self, index_matrix: &Matrix<u8>, text_buffer: &mut Matrix<char>) {
-		let w = min(text_buffer.width(), index_matrix.width());
+	/**
+	 * Cells whose family is nonzero use the corresponding charset in `FAMILY_CHARSETS`,
+	 * with the char index scaled down to its length
+	 */
+	pub fn write_textbuffer(&self, index_matrix: &Matrix<u8>, family_matrix: &Matrix<u8>, text_buffer: &mut Matrix<char>) {
+		self.write_textbuffer_at(index_matrix, family_matrix, None, text_buffer, 0);
+	}
+
+	/**
+	 * Same as `write_textbuffer()`, but starting at column `offset_x` of the text buffer (used by split view)
+	 * 
+	 * edge_matrix - optional glyphs (see `write_index_matrix()`) which take the place of the ramp's char, where not '\0'
+	 */
+	pub fn write_textbuffer_at(&self, index_matrix: &Matrix<u8>, family_matrix: &Matrix<u8>, edge_matrix: Option<&Matrix<char>>,
+			text_buffer: &mut Matrix<char>, offset_x: usize) {
+		let w = min(text_buffer.width().saturating_sub(offset_x), index_matrix.width());
 		let h = min(text_buffer.height(), index_matrix.height());
 		for y in 0..h {
 			for x in 0..w {
-				let i = index_matrix.get(x, y);
-				let char = self.chars[i as usize];
-				text_buffer.set(x, y, char);
+				let i = index_matrix.get(x, y) as usize;
+				let family = if x < family_matrix.width() && y < family_matrix.height() { family_matrix.get(x, y) } else { 0 };
+				let edge = match edge_matrix {
+					Some(m) if x < m.width() && y < m.height() => m.get(x, y),
+					_ => '\0'
+				};
+				let char = if edge != '\0' {
+					edge
+				} else if family == 0 {
+					self.chars[(i + self.ramp_offset) % self.chars.len()]
+				} else {
+					let charset = FAMILY_CHARSETS[(family as usize - 1) % FAMILY_CHARSETS.len()];
+					let n = charset.chars().count();
+					let offset = self.ramp_offset * n / self.chars.len();
+					charset.chars().nth((i * n / self.chars.len() + offset) % n).unwrap()
+				};
+				text_buffer.set(offset_x + x, y, char);
 			}
 		}		
 	}
 
-    pub fn to_char_index(&self, mut value: f64) -> u8 {
+	/**
+	 * Writes the char index of each value in `fractal_matrix` to `index_matrix`, using the mapping's dithering
+	 * 
+	 * curve - optional lookup table to use in place of floor, ceil and mapping (see `curve_ratio()`)
+	 * element_ar - the width/height ratio of a character cell
+	 * edge_matrix - gets the directional glyphs when `use_edges` is on, and otherwise gets cleared to '\0'
+	 */
+	pub fn write_index_matrix(&self, fractal_matrix: &Matrix<u16>, curve: Option<&Vec<f64>>, element_ar: f64,
+			index_matrix: &mut Matrix<u8>, edge_matrix: &mut Matrix<char>) {
+		let w = min(fractal_matrix.width(), index_matrix.width());
+		let h = min(fractal_matrix.height(), index_matrix.height());
+		let mut levels = Matrix::<f64>::new(w, h);
+		for y in 0..h {
+			for x in 0..w {
+				let value = fractal_matrix.get(x, y) as f64;
+				let ratio = match curve {
+					Some(curve) => Asciifier::curve_ratio(value, curve),
+					None => self.to_ratio(value)
+				};
+				levels.set(x, y, ratio / self.step);
+			}
+		}
+		self.dither().write_indices(&levels, self.chars.len(), index_matrix);
+
+		if self.use_edges {
+			Edges::write(&levels, self.chars.len(), element_ar, edge_matrix);
+		} else {
+			for y in 0..edge_matrix.height() {
+				for x in 0..edge_matrix.width() {
+					edge_matrix.set(x, y, '\0');
+				}
+			}
+		}
+	}
+
+    pub fn to_char_index(&self, value: f64) -> u8 {
+    	let ratio = self.to_ratio(value);
+    	self.ratio_to_index(ratio)
+    }
+
+	/**
+	 * Looks up `value` in a table of ratios indexed by value (see `ExposureUtil::calc_equalized()`), 
+	 * which takes the place of floor, ceil and mapping. Values past the end of the table get the last ratio.
+	 */
+	fn curve_ratio(value: f64, curve: &Vec<f64>) -> f64 {
+		let i = value.max(0.0) as usize;
+		if i < curve.len() { curve[i] } else { curve[curve.len() - 1] }
+	}
+
+	/**
+	 * The ratios which the current floor, ceil, and mapping give to the values 0 to `max_val`,
+	 * in the same form as `write_index_matrix()` expects
+	 */
+	pub fn to_curve(&self, max_val: u16) -> Vec<f64> {
+		(0..(max_val as usize + 1)).map(|v| self.to_ratio(v as f64)).collect()
+	}
+
+	/**
+	 * Normalizes `value` using floor and ceil, and applies the mapping; returns 0 to 1
+	 */
+    fn to_ratio(&self, mut value: f64) -> f64 {
 
     	if value < self.floor {
     		value = self.floor;
@@ -113,16 +276,22 @@ impl Asciifier {
     	
     	let ratio = (value - self.floor) / self.range;
     	
-    	let biased_a = ratio.sqrt();    
-    	let biased_b = ratio * ratio;  
-    	
-    	let ratio = math::map(self.bias, -1.0, 1.0,  biased_a, biased_b);
-    	
-    	// less hump:
-		// biased_a = (((ratio * 3.0) + 1.0) as f64).ln() * (5.0/7.0);
-		// even less:
-		// ratio_b = ((ratio + 1.0) as f64).ln() * (10.0/7.0);
-		
+    	match self.mapping {
+    		Mapping::Bias => {
+    			self.tone_curve.apply(ratio, self.bias)
+    		},
+    		Mapping::Lines => {
+    			// smoothstep
+    			let t = ((ratio - LINES_EDGE) / (1.0 - LINES_EDGE)).max(0.0).min(1.0);
+    			t * t * (3.0 - 2.0 * t)
+    		},
+    		Mapping::Signed => {
+    			(ratio * 2.0 - 1.0).abs()
+    		}
+    	}
+    }
+
+    fn ratio_to_index(&self, ratio: f64) -> u8 {
     	let mut i = (ratio / self.step) as usize;
         if i > self.chars.len() - 1 {
             i = self.chars.len() - 1;
@@ -131,7 +300,7 @@ impl Asciifier {
     }
     
     pub fn to_char(&self, value: f64) -> char {
-		let i = self.to_char_index(value);
-		self.chars[i as usize]
+		let i = self.to_char_index(value) as usize;
+		self.chars[(i + self.ramp_offset) % self.chars.len()]
     }
 }
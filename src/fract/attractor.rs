@@ -0,0 +1,234 @@
+use std::fmt;
+use std::thread;
+use leelib::vector2::Vector2f;
+use fract::fractalcalc::{FractalCalc, FractalSpecs};
+use fract::buddhabrot::{Density, Rng};
+
+
+// iterations done before points start getting plotted, so that orbits have settled onto the attractor
+const WARMUP: usize = 100;
+
+// sierpinski triangle corners (y pointing down, so that the apex is at the top)
+const SIERPINSKI_CORNERS: [Vector2f; 3] = [
+	Vector2f { x: 0.5, y: 0.0 }, Vector2f { x: 0.0, y: 0.866 }, Vector2f { x: 1.0, y: 0.866 } ];
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PointSystemKind {
+	// x' = sin(a y) + c cos(a x), y' = sin(b x) + d cos(b y)
+	Clifford,
+	// (Peter) de Jong: x' = sin(a y) - cos(b x), y' = sin(c x) - cos(d y)
+	DeJong,
+	// iterated function systems, which don't use the parameters:
+	Fern,
+	Sierpinski,
+}
+
+impl PointSystemKind {
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			PointSystemKind::Clifford => "Clifford",
+			PointSystemKind::DeJong => "De Jong",
+			PointSystemKind::Fern => "Barnsley fern",
+			PointSystemKind::Sierpinski => "Sierpinski",
+		}
+	}
+
+	/**
+	 * Whether the system has parameters (which can be tweened)
+	 */
+	pub fn has_params(&self) -> bool {
+		match *self {
+			PointSystemKind::Clifford | PointSystemKind::DeJong => true,
+			_ => false
+		}
+	}
+}
+
+
+/**
+ * A strange attractor or iterated function system, whose points accumulate into a `Density`
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub struct PointSystem {
+	pub kind: PointSystemKind,
+	pub params: [f64; 4],
+}
+
+impl PointSystem {
+
+	/**
+	 * Parses lines like "clifford -1.4, 1.6, 1.0, 0.7", "dejong 1.4, -2.3, 2.4, -2.1", "fern", or "sierpinski"
+	 */
+	pub fn parse(s: &str) -> Result<PointSystem, String> {
+		let s = s.trim();
+		let (name, rest) = match s.find(' ') {
+			Some(i) => (&s[..i], s[i..].trim()),
+			None => (s, "")
+		};
+		let kind = match &name.to_lowercase()[..] {
+			"clifford" => PointSystemKind::Clifford,
+			"dejong" => PointSystemKind::DeJong,
+			"fern" => PointSystemKind::Fern,
+			"sierpinski" => PointSystemKind::Sierpinski,
+			_ => return Err(format!("Unknown point system '{}'", name))
+		};
+
+		let mut params = [0.0; 4];
+		if kind.has_params() {
+			let v: Vec<&str> = rest.split(',').collect();
+			if v.len() != 4 {
+				return Err(format!("{} requires 4 parameters", kind.name()));
+			}
+			for i in 0..4 {
+				match v[i].trim().parse::<f64>() {
+					Ok(val) => params[i] = val,
+					Err(_) => return Err(format!("Bad parameter '{}'", v[i].trim()))
+				}
+			}
+		}
+		Ok(PointSystem { kind: kind, params: params })
+	}
+
+	/**
+	 * Tweens the parameters (only meaningful when both are of the same kind)
+	 */
+	pub fn interpolate(from: &PointSystem, to: &PointSystem, t: f64) -> PointSystem {
+		let mut params = [0.0; 4];
+		for i in 0..4 {
+			params[i] = from.params[i] + (to.params[i] - from.params[i]) * t;
+		}
+		PointSystem { kind: to.kind, params: params }
+	}
+
+	/**
+	 * The center and size of the rectangle that contains all of the system's points
+	 */
+	pub fn bounds(&self) -> (Vector2f, Vector2f) {
+		let p = &self.params;
+		match self.kind {
+			PointSystemKind::Clifford => {
+				(Vector2f::new(0.0, 0.0), Vector2f::new(2.0 + 2.0 * p[2].abs(), 2.0 + 2.0 * p[3].abs()))
+			},
+			PointSystemKind::DeJong => (Vector2f::new(0.0, 0.0), Vector2f::new(4.0, 4.0)),
+			PointSystemKind::Fern => (Vector2f::new(0.25, -5.0), Vector2f::new(5.5, 10.0)),
+			PointSystemKind::Sierpinski => (Vector2f::new(0.5, 0.433), Vector2f::new(1.0, 0.866)),
+		}
+	}
+
+	/**
+	 * Returns the next point of the orbit
+	 */
+	pub fn next(&self, pt: Vector2f, rng: &mut Rng) -> Vector2f {
+		let p = &self.params;
+		match self.kind {
+			PointSystemKind::Clifford => Vector2f::new(
+				(p[0] * pt.y).sin() + p[2] * (p[0] * pt.x).cos(),
+				(p[1] * pt.x).sin() + p[3] * (p[1] * pt.y).cos()),
+			PointSystemKind::DeJong => Vector2f::new(
+				(p[0] * pt.y).sin() - (p[1] * pt.x).cos(),
+				(p[2] * pt.x).sin() - (p[3] * pt.y).cos()),
+			PointSystemKind::Fern => {
+				// (the usual affine maps, but with y pointing down)
+				let r = rng.next_f64();
+				if r < 0.01 {
+					Vector2f::new(0.0, 0.16 * pt.y)
+				} else if r < 0.86 {
+					Vector2f::new(0.85 * pt.x - 0.04 * pt.y, 0.04 * pt.x + 0.85 * pt.y - 1.6)
+				} else if r < 0.93 {
+					Vector2f::new(0.2 * pt.x + 0.26 * pt.y, -0.23 * pt.x + 0.22 * pt.y - 1.6)
+				} else {
+					Vector2f::new(-0.15 * pt.x - 0.28 * pt.y, -0.26 * pt.x + 0.24 * pt.y - 0.44)
+				}
+			},
+			PointSystemKind::Sierpinski => {
+				let corner = SIERPINSKI_CORNERS[((rng.next_f64() * 3.0) as usize) % 3];
+				Vector2f::new((pt.x + corner.x) * 0.5, (pt.y + corner.y) * 0.5)
+			}
+		}
+	}
+}
+
+impl fmt::Display for PointSystem {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.kind.has_params() {
+			write!(f, "{} ({:.2}, {:.2}, {:.2}, {:.2})", self.kind.name(),
+					self.params[0], self.params[1], self.params[2], self.params[3])
+		} else {
+			write!(f, "{}", self.kind.name())
+		}
+	}
+}
+
+
+/**
+ * 'Static' class
+ * Iterates a `PointSystem` and accumulates its points into a `Density` (which has a single channel)
+ */
+pub struct Attractor;
+
+impl Attractor {
+
+	/**
+	 * Plots `num_points` points, split among `specs.num_threads` threads, each of which follows its own orbit
+	 * (from a random starting point) and accumulates into its own `Density`, which then get added to `density`
+	 *
+	 * seed - should be different each call
+	 */
+	pub fn accumulate(specs: &FractalSpecs, system: PointSystem, center: Vector2f, width: f64, rotation: f64,
+			density: &mut Density, num_points: usize, seed: u32) {
+
+		let num_threads = if specs.use_multi_threads { specs.num_threads } else { 1 };
+		let mut handles = Vec::new();
+
+		for i in 0..num_threads {
+			let spec = specs.clone();
+			let w = density.channels[0].width();
+			let h = density.channels[0].height();
+			let n = num_points / num_threads + if i < num_points % num_threads { 1 } else { 0 };
+			let thread_seed = FractalCalc::hash(seed as usize, i, 0x2c9f);
+
+			handles.push(thread::spawn(move || {
+				let mut accumulator = Density::new(w, h, 1);
+				Attractor::plot(&spec, system, center, width, rotation, &mut accumulator, n, thread_seed);
+				accumulator
+			}));
+		}
+
+		for handle in handles {
+			let accumulator = handle.join().unwrap();
+			density.add(&accumulator);
+		}
+	}
+
+	fn plot(specs: &FractalSpecs, system: PointSystem, center: Vector2f, width: f64, rotation: f64,
+			density: &mut Density, num_points: usize, seed: u32) {
+
+		let w = density.channels[0].width();
+		let h = density.channels[0].height();
+		let height = FractalCalc::get_height(specs, w, h, width);
+		let element_w = width / w as f64;
+		let element_h = height / h as f64;
+
+		let mut rng = Rng::new(seed);
+		let mut pt = Vector2f::new(rng.next_f64() - 0.5, rng.next_f64() - 0.5);
+		for _ in 0..WARMUP {
+			pt = system.next(pt, &mut rng);
+		}
+
+		for _ in 0..num_points {
+			pt = system.next(pt, &mut rng);
+			// inverse of the cell-to-point mapping in `FractalCalc::write_matrix_section`
+			let d = Vector2f::rotate(Vector2f::new(pt.x - center.x, pt.y - center.y), -rotation);
+			let x = (d.x / element_w + w as f64 / 2.0).round();
+			let y = (d.y / element_h + h as f64 / 2.0).round();
+			if x >= 0.0 && y >= 0.0 && x < w as f64 && y < h as f64 {
+				let v = density.channels[0].get(x as usize, y as usize);
+				density.channels[0].set(x as usize, y as usize, v + 1);
+			}
+		}
+
+		density.num_samples += num_points as u64;
+	}
+}
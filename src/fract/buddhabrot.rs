@@ -0,0 +1,254 @@
+extern crate num;
+
+use std::thread;
+use self::num::complex::{Complex, Complex64};
+use leelib::vector2::Vector2f;
+use leelib::matrix::Matrix;
+use fract::fractalcalc::{FractalCalc, FractalSpecs};
+
+
+// the region of the complex plane that c values are sampled from 
+// (c values outside of radius 2 escape immediately, and contribute nothing)
+const SAMPLE_MIN: Vector2f = Vector2f { x: -2.0, y: -2.0 };
+const SAMPLE_MAX: Vector2f = Vector2f { x: 2.0, y: 2.0 };
+
+// nebulabrot: iteration limits of the three channels, as ratios of `FractalSpecs::max_val`
+const NEBULA_LIMIT_RATIOS: [f64; 3] = [ 1.0, 0.1, 0.01 ];
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BuddhaMode {
+	// hit counts of escaping orbits
+	Buddhabrot,
+	// ... split into channels by the orbit's length, each channel being drawn with its own character family
+	Nebulabrot,
+	// hit counts of orbits which don't escape
+	Anti,
+}
+
+impl BuddhaMode {
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			BuddhaMode::Buddhabrot => "Buddhabrot",
+			BuddhaMode::Nebulabrot => "Nebulabrot",
+			BuddhaMode::Anti => "Anti-Buddhabrot",
+		}
+	}
+
+	pub fn num_channels(&self) -> usize {
+		match *self {
+			BuddhaMode::Nebulabrot => NEBULA_LIMIT_RATIOS.len(),
+			_ => 1
+		}
+	}
+}
+
+
+/**
+ * Accumulated orbit hit counts for the current viewport, one matrix per channel
+ */
+pub struct Density {
+	pub channels: Vec<Matrix<u32>>,
+	pub num_samples: u64,
+}
+
+impl Density {
+
+	pub fn new(width: usize, height: usize, num_channels: usize) -> Self {
+		Density { channels: vec![Matrix::new(width, height); num_channels], num_samples: 0 }
+	}
+
+	pub fn add(&mut self, other: &Density) {
+		for c in 0..self.channels.len() {
+			for y in 0..self.channels[c].height() {
+				for x in 0..self.channels[c].width() {
+					let v = self.channels[c].get(x, y) + other.channels[c].get(x, y);
+					self.channels[c].set(x, y, v);
+				}
+			}
+		}
+		self.num_samples += other.num_samples;
+	}
+}
+
+
+/**
+ * 'Static' class
+ * Samples random c values and accumulates the points of their orbits into a `Density`
+ */
+pub struct Buddhabrot;
+
+impl Buddhabrot {
+
+	/**
+	 * Samples `num_samples` c values, split among `specs.num_threads` threads,
+	 * each of which accumulates into its own `Density`, which then get added to `density`.
+	 *
+	 * The c values are spread evenly over SAMPLE_MIN to SAMPLE_MAX whatever the viewport is, 
+	 * so the smaller the viewport, the fewer of the orbits pass thru it.
+	 *
+	 * seed - should be different each call, so that each batch of samples is different
+	 */
+	pub fn accumulate(specs: &FractalSpecs, mode: BuddhaMode, center: Vector2f, width: f64, rotation: f64,
+			density: &mut Density, num_samples: usize, seed: u32) {
+
+		let num_threads = if specs.use_multi_threads { specs.num_threads } else { 1 };
+		let mut handles = Vec::new();
+
+		for i in 0..num_threads {
+			let spec = specs.clone();
+			let w = density.channels[0].width();
+			let h = density.channels[0].height();
+			let n = num_samples / num_threads + if i < num_samples % num_threads { 1 } else { 0 };
+			let thread_seed = FractalCalc::hash(seed as usize, i, 0x5bd1);
+
+			handles.push(thread::spawn(move || {
+				let mut accumulator = Density::new(w, h, mode.num_channels());
+				Buddhabrot::sample(&spec, mode, center, width, rotation, &mut accumulator, n, thread_seed);
+				accumulator
+			}));
+		}
+
+		for handle in handles {
+			let accumulator = handle.join().unwrap();
+			density.add(&accumulator);
+		}
+	}
+
+	fn sample(specs: &FractalSpecs, mode: BuddhaMode, center: Vector2f, width: f64, rotation: f64,
+			density: &mut Density, num_samples: usize, seed: u32) {
+
+		let w = density.channels[0].width();
+		let h = density.channels[0].height();
+		let height = FractalCalc::get_height(specs, w, h, width);
+		let element_w = width / w as f64;
+		let element_h = height / h as f64;
+		let max_iterations = specs.max_val as usize;
+
+		let mut limits = [max_iterations; 3];
+		if mode == BuddhaMode::Nebulabrot {
+			for i in 0..NEBULA_LIMIT_RATIOS.len() {
+				limits[i] = (max_iterations as f64 * NEBULA_LIMIT_RATIOS[i]) as usize;
+			}
+		}
+
+		let mut rng = Rng::new(seed);
+		let mut orbit: Vec<Complex64> = Vec::with_capacity(max_iterations);
+
+		for _ in 0..num_samples {
+
+			let c = Complex {
+				re: SAMPLE_MIN.x + rng.next_f64() * (SAMPLE_MAX.x - SAMPLE_MIN.x),
+				im: SAMPLE_MIN.y + rng.next_f64() * (SAMPLE_MAX.y - SAMPLE_MIN.y) };
+
+			if mode != BuddhaMode::Anti && Buddhabrot::is_in_main_bulbs(c) {
+				// known not to escape
+				continue;
+			}
+
+			orbit.clear();
+			let mut z = Complex { re: 0.0, im: 0.0 };
+			let mut escaped = false;
+			for _ in 0..max_iterations {
+				z = z * z + c;
+				if z.norm_sqr() > 4.0 {
+					escaped = true;
+					break;
+				}
+				orbit.push(z);
+			}
+
+			let keep = if mode == BuddhaMode::Anti { ! escaped } else { escaped };
+			if ! keep {
+				continue;
+			}
+
+			for ch in 0..density.channels.len() {
+				if orbit.len() > limits[ch] {
+					continue;
+				}
+				for p in orbit.iter() {
+					// inverse of the cell-to-point mapping in `FractalCalc::write_matrix_section`
+					let d = Vector2f::rotate(Vector2f::new(p.re - center.x, p.im - center.y), -rotation);
+					let x = (d.x / element_w + w as f64 / 2.0).round();
+					let y = (d.y / element_h + h as f64 / 2.0).round();
+					if x >= 0.0 && y >= 0.0 && x < w as f64 && y < h as f64 {
+						let v = density.channels[ch].get(x as usize, y as usize);
+						density.channels[ch].set(x as usize, y as usize, v + 1);
+					}
+				}
+			}
+		}
+
+		density.num_samples += num_samples as u64;
+	}
+
+	/**
+	 * Main cardioid and period-2 bulb
+	 */
+	fn is_in_main_bulbs(c: Complex64) -> bool {
+		let q = (c.re - 0.25) * (c.re - 0.25) + c.im * c.im;
+		if q * (q + (c.re - 0.25)) < 0.25 * c.im * c.im {
+			return true;
+		}
+		(c.re + 1.0) * (c.re + 1.0) + c.im * c.im < 1.0 / 16.0
+	}
+
+	/**
+	 * Writes the density to the fractal matrix, scaled to the range 0 to max_val (square root, for contrast),
+	 * along with the family matrix (for the nebulabrot, the family is the channel (+1) with the highest relative density)
+	 */
+	pub fn write_matrix(density: &Density, max_val: u16, matrix: &mut Matrix<u16>, families: &mut Matrix<u8>) {
+
+		let num_channels = density.channels.len();
+		let mut max_densities = vec![1u32; num_channels];
+		for ch in 0..num_channels {
+			for v in &density.channels[ch] {
+				if v > max_densities[ch] {
+					max_densities[ch] = v;
+				}
+			}
+		}
+
+		for y in 0..matrix.height() {
+			for x in 0..matrix.width() {
+				let mut sum = 0.0;
+				let mut family = 0;
+				let mut family_ratio = 0.0;
+				for ch in 0..num_channels {
+					let ratio = density.channels[ch].get(x, y) as f64 / max_densities[ch] as f64;
+					sum += ratio;
+					if num_channels > 1 && ratio > family_ratio {
+						family_ratio = ratio;
+						family = ch + 1;
+					}
+				}
+				let value = (sum / num_channels as f64).sqrt() * max_val as f64;
+				matrix.set(x, y, value as u16);
+				families.set(x, y, family as u8);
+			}
+		}
+	}
+}
+
+
+/**
+ * Xorshift
+ */
+pub struct Rng {
+	state: u32,
+}
+
+impl Rng {
+	pub fn new(seed: u32) -> Self {
+		Rng { state: if seed == 0 { 0x9e3779b9 } else { seed } }
+	}
+
+	pub fn next_f64(&mut self) -> f64 {
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 17;
+		self.state ^= self.state << 5;
+		self.state as f64 / 4294967296.0
+	}
+}
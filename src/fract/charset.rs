@@ -0,0 +1,80 @@
+use fract::asciifier::{CHARSET1, CHARSET2};
+
+
+// a char index has to fit in a u8 (see `View::index_matrix()`)
+const MAX_LENGTH: usize = 255;
+
+
+/**
+ * A named 'ramp' of chars, ordered from lightest to heaviest
+ */
+#[derive(Clone)]
+pub struct Charset {
+	pub name: String,
+	pub chars: String,
+}
+
+impl Charset {
+
+	/**
+	 * Validates a user-supplied ramp
+	 */
+	pub fn parse(name: &str, s: &str) -> Result<Charset, String> {
+		let n = s.chars().count();
+		if n < 2 {
+			return Err("Charset needs at least 2 chars".to_string());
+		}
+		if n > MAX_LENGTH {
+			return Err(format!("Charset can't be longer than {} chars", MAX_LENGTH));
+		}
+		if s.chars().any(|c| c.is_control()) {
+			return Err("Charset can't contain control chars".to_string());
+		}
+		Ok(Charset { name: name.to_string(), chars: s.to_string() })
+	}
+
+	/**
+	 * The built-in ramps. The first one is the default.
+	 */
+	pub fn presets() -> Vec<Charset> {
+		vec![
+			Charset { name: "extended".to_string(), chars: CHARSET2.to_string() },
+			Charset { name: "ascii".to_string(), chars: CHARSET1.to_string() },
+			Charset { name: "minimal".to_string(), chars: " .:-=+*#%@".to_string() },
+			Charset { name: "blocks".to_string(), chars: " ░▒▓█".to_string() },
+			// by the number of raised dots, 0 thru 8
+			Charset { name: "braille".to_string(), chars: " ⠁⠃⠇⠏⠟⠿⡿⣿".to_string() },
+		]
+	}
+}
+
+
+/**
+ * The presets, plus any user-supplied ramps (which come first)
+ */
+pub struct CharsetList {
+	list: Vec<Charset>,
+	pub index: usize,
+}
+
+impl CharsetList {
+
+	pub fn new(custom: &Vec<Charset>) -> Self {
+		let mut list = custom.clone();
+		list.extend(Charset::presets());
+		CharsetList { list: list, index: 0 }
+	}
+
+	pub fn get(&self) -> &Charset {
+		&self.list[self.index]
+	}
+
+	/**
+	 * Steps forward or back thru the list, wrapping around
+	 */
+	pub fn cycle(&mut self, steps: i32) -> &Charset {
+		let n = self.list.len() as i32;
+		self.index = (((self.index as i32 + steps) % n + n) % n) as usize;
+		&self.list[self.index]
+	}
+}
@@ -0,0 +1,168 @@
+extern crate num;
+
+use std::f64::consts::PI;
+use self::num::complex::{Complex, Complex64};
+
+
+// larger escape radius than the plain escape-time kernels use, which the averaging methods need
+const BAILOUT: f64 = 100.0;
+
+// orbit traps: trap distances past this count as 'not trapped'
+const TRAP_SCALE: f64 = 1.0;
+const POINT_TRAP: Complex64 = Complex { re: 0.0, im: 0.0 };
+
+// stripe average density
+const STRIPE_DENSITY: f64 = 5.0;
+
+
+/**
+ * Alternatives to the escape-time iteration count, as the value of a cell
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Coloring {
+	// plain iteration count
+	Escape,
+	// closeness of the orbit to a point, to a line (the real axis), and to a cross (both axes)
+	PointTrap,
+	LineTrap,
+	CrossTrap,
+	// triangle inequality average
+	TriangleAverage,
+	// average of sin(arg(z)) over the orbit
+	StripeAverage,
+	// angle of z once it escapes (binary decomposition, but with the full angle rather than just its sign)
+	FinalAngle,
+	// |z| at the last iteration, for points that don't escape; escaping points are empty
+	InteriorLastZ,
+}
+
+impl Coloring {
+
+	pub fn next(&self) -> Coloring {
+		match *self {
+			Coloring::Escape => Coloring::PointTrap,
+			Coloring::PointTrap => Coloring::LineTrap,
+			Coloring::LineTrap => Coloring::CrossTrap,
+			Coloring::CrossTrap => Coloring::TriangleAverage,
+			Coloring::TriangleAverage => Coloring::StripeAverage,
+			Coloring::StripeAverage => Coloring::FinalAngle,
+			Coloring::FinalAngle => Coloring::InteriorLastZ,
+			Coloring::InteriorLastZ => Coloring::Escape,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Coloring::Escape => "escape time",
+			Coloring::PointTrap => "point orbit trap",
+			Coloring::LineTrap => "line orbit trap",
+			Coloring::CrossTrap => "cross orbit trap",
+			Coloring::TriangleAverage => "triangle inequality average",
+			Coloring::StripeAverage => "stripe average",
+			Coloring::FinalAngle => "final angle",
+			Coloring::InteriorLastZ => "interior last |z|",
+		}
+	}
+
+	/**
+	 * Iterates z = z^2 + c starting at `z`, and returns the value for the given coloring method,
+	 * normalized to the range 0-1
+	 *
+	 * (`Coloring::Escape` is handled by `FractalCalc` itself)
+	 */
+	pub fn get_ratio(&self, mut z: Complex64, c: Complex64, max_iterations: u16) -> f64 {
+
+		let c_abs = c.norm_sqr().sqrt();
+
+		let mut trap_min = ::std::f64::MAX;
+		let mut sum = 0.0;  // running sum for the averaging methods
+		let mut prev_sum = 0.0;  // ... as of the previous iteration, for smoothing
+		let mut count = 0;
+
+		let mut escaped = false;
+		for _ in 0..max_iterations {
+
+			let z_sq = z * z;
+			let z_next = z_sq + c;
+
+			match *self {
+				Coloring::PointTrap => {
+					let d = (z_next - POINT_TRAP).norm_sqr().sqrt();
+					if d < trap_min { trap_min = d; }
+				},
+				Coloring::LineTrap => {
+					let d = z_next.im.abs();
+					if d < trap_min { trap_min = d; }
+				},
+				Coloring::CrossTrap => {
+					let d = z_next.re.abs().min(z_next.im.abs());
+					if d < trap_min { trap_min = d; }
+				},
+				Coloring::TriangleAverage => {
+					// |z^2 + c| lies between | |z^2| - |c| | and |z^2| + |c|; where it lies gets averaged
+					let z_sq_abs = z_sq.norm_sqr().sqrt();
+					let lower = (z_sq_abs - c_abs).abs();
+					let upper = z_sq_abs + c_abs;
+					if upper - lower > 0.0 && count > 0 {
+						prev_sum = sum;
+						sum += (z_next.norm_sqr().sqrt() - lower) / (upper - lower);
+					}
+					count += 1;
+				},
+				Coloring::StripeAverage => {
+					prev_sum = sum;
+					sum += 0.5 + 0.5 * (STRIPE_DENSITY * z_next.im.atan2(z_next.re)).sin();
+					count += 1;
+				},
+				_ => {}
+			}
+
+			z = z_next;
+			if z.norm_sqr() > BAILOUT * BAILOUT {
+				escaped = true;
+				break;
+			}
+		}
+
+		match *self {
+			Coloring::PointTrap | Coloring::LineTrap | Coloring::CrossTrap => {
+				1.0 - (trap_min / TRAP_SCALE).min(1.0)
+			},
+			Coloring::TriangleAverage | Coloring::StripeAverage => {
+				if ! escaped || count < 2 {
+					return 0.0;
+				}
+				// interpolate between the last two averages using the fractional iteration count,
+				// to get rid of banding
+				let n = if *self == Coloring::TriangleAverage { count - 1 } else { count };
+				let avg = sum / n as f64;
+				let prev_avg = if n > 1 { prev_sum / (n - 1) as f64 } else { avg };
+				let frac = Coloring::smooth_fraction(z);
+				prev_avg + (avg - prev_avg) * frac
+			},
+			Coloring::FinalAngle => {
+				if ! escaped {
+					return 0.0;
+				}
+				(z.im.atan2(z.re) + PI) / (2.0 * PI)
+			},
+			Coloring::InteriorLastZ => {
+				if escaped {
+					return 0.0;
+				}
+				// a non-escaping orbit stays within |z| <= 2
+				(z.norm_sqr().sqrt() / 2.0).min(1.0)
+			},
+			Coloring::Escape => 0.0
+		}
+	}
+
+	/**
+	 * The fractional part of the 'smooth' (continuous) iteration count, in range 0-1
+	 */
+	fn smooth_fraction(z: Complex64) -> f64 {
+		let log_zn = z.norm_sqr().ln() / 2.0;
+		let nu = (log_zn / BAILOUT.ln()).log2();
+		(1.0 - nu).max(0.0).min(1.0)
+	}
+}
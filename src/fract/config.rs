@@ -0,0 +1,283 @@
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::process;
+use fract::newton::Polynomial;
+use fract::formula::Formula;
+use fract::lyapunov::Sequence;
+use fract::hud::HudPosition;
+use fract::tonecurve::ToneCurve;
+use fract::charset::Charset;
+
+
+static USAGE_TEXT: &'static str = "\
+Usage: fractal-term-rs [options]
+
+Options:
+  --aspect <ratio>   width/height ratio of a terminal character cell (eg, 0.45);
+                     auto-detected where possible
+  --newton <poly>    polynomial for the Newton fractal view (eg, \"z^3 - 1\");
+                     complex coefficients are written as 2i or (1+2i)
+  --lyapunov <seq>   A/B sequence for the Lyapunov view (eg, AABAB)
+  --formula <f>      custom iteration formula in terms of z and c, for the
+                     Mandelbrot and Julia views (eg, \"z^3 + c\", \"sin(z)*c\");
+                     can also be entered at runtime using the ':' key
+  --hud <position>   where the status-line HUD ('i' key) goes: top or bottom
+  --tone <curve>     starting tone curve ('t' key): bias, linear, gamma:<n>,
+                     log:<n>, sigmoid:<n>, or piecewise points such as
+                     0,0/0.3,0.6/1,1 (which the 't' key then uses)
+  --charset <ramp>   chars to draw with, from lightest to heaviest (eg, \" .oO@\");
+                     can be given more than once; the 'w' key cycles thru
+                     these and the built-in ones (in the config file, quote
+                     the value to keep a leading space)
+  --help             show this text
+
+Options can also be set in ~/.fractal-term-rs, one per line, as in:
+  aspect = 0.45
+";
+
+const CONFIG_FILENAME: &'static str = ".fractal-term-rs";
+
+
+/**
+ * User settings, which come from the config file and then from the command line
+ * (command line wins)
+ */
+pub struct Config {
+	pub char_aspect_ratio: Option<f64>,
+	pub newton_polynomial: Option<Polynomial>,
+	pub lyapunov_sequence: Option<Sequence>,
+	pub formula_text: String,  // empty for the built-in formula
+	pub hud_position: HudPosition,
+	pub tone_curve: Option<ToneCurve>,
+	pub charsets: Vec<Charset>,
+
+	// problems with lines of the config file (which can't just be printed, since the terminal gets taken over)
+	pub warnings: Vec<String>,
+
+	// lines of the config file, kept so that `save_value()` can preserve the rest of the file
+	file_lines: Vec<String>,
+}
+
+impl Config {
+
+	/**
+	 * Prints usage and exits on bad command line arguments.
+	 * Bad lines in the config file only get a warning (see `warnings`).
+	 */
+	pub fn load() -> Config {
+
+		let mut config = Config { char_aspect_ratio: None, newton_polynomial: None, lyapunov_sequence: None, formula_text: "".to_string(), 
+				hud_position: HudPosition::Bottom, tone_curve: None, charsets: Vec::new(), warnings: Vec::new(), file_lines: Vec::new() };
+
+		// config file
+		match Config::read_file() {
+			Some(text) => {
+				for line in text.lines() {
+					config.file_lines.push(line.to_string());
+					match Config::parse_line(line) {
+						Some((key, value)) => {
+							match config.apply(&key, &value) {
+								Err(e) => config.warnings.push(format!("{}: {}", CONFIG_FILENAME, e)),
+								_ => {}
+							}
+						},
+						None => {}
+					}
+				}
+			},
+			None => {}
+		}
+
+		// command line
+		let args: Vec<String> = env::args().skip(1).collect();
+		let mut i = 0;
+		while i < args.len() {
+			let arg = &args[i];
+			if arg == "--help" || arg == "-h" {
+				Config::exit_with_usage(None);
+			}
+			if ! arg.starts_with("--") {
+				Config::exit_with_usage(Some(format!("Unexpected argument: {}", arg)));
+			}
+			// either '--key=value' or '--key value'
+			let (key, value) = match arg.find('=') {
+				Some(index) => (arg[2..index].to_string(), arg[(index + 1)..].to_string()),
+				None => {
+					i += 1;
+					if i >= args.len() {
+						Config::exit_with_usage(Some(format!("Missing value for {}", arg)));
+					}
+					(arg[2..].to_string(), args[i].clone())
+				}
+			};
+			match config.apply(&key, &value) {
+				Err(e) => Config::exit_with_usage(Some(e)),
+				_ => {}
+			}
+			i += 1;
+		}
+
+		config
+	}
+
+	fn apply(&mut self, key: &str, value: &str) -> Result<(), String> {
+		match key {
+			"aspect" => {
+				match value.parse::<f64>() {
+					Ok(v) if v > 0.0 && v.is_finite() => {
+						self.char_aspect_ratio = Some(v);
+						Ok(())
+					},
+					_ => Err(format!("Bad value for aspect: {}", value))
+				}
+			},
+			"newton" => {
+				match Polynomial::parse(value) {
+					Ok(p) => {
+						self.newton_polynomial = Some(p);
+						Ok(())
+					},
+					Err(e) => Err(format!("Bad value for newton: {} ({})", value, e))
+				}
+			},
+			"lyapunov" => {
+				match Sequence::parse(value) {
+					Ok(s) => {
+						self.lyapunov_sequence = Some(s);
+						Ok(())
+					},
+					Err(e) => Err(format!("Bad value for lyapunov: {} ({})", value, e))
+				}
+			},
+			"formula" => {
+				match Formula::parse(value) {
+					Ok(_) => {
+						self.formula_text = value.to_string();
+						Ok(())
+					},
+					Err(e) => Err(format!("Bad value for formula: {} ({})", value, e))
+				}
+			},
+			"hud" => {
+				match HudPosition::parse(value) {
+					Some(p) => {
+						self.hud_position = p;
+						Ok(())
+					},
+					None => Err(format!("Bad value for hud: {} (must be top or bottom)", value))
+				}
+			},
+			"charset" => {
+				// (config file values get trimmed, so they can be quoted to keep a leading space)
+				let is_quoted = value.len() >= 2 && value.starts_with('"') && value.ends_with('"');
+				let value = if is_quoted { &value[1..(value.len() - 1)] } else { value };
+				let name = format!("custom {}", self.charsets.len() + 1);
+				match Charset::parse(&name, value) {
+					Ok(c) => {
+						self.charsets.push(c);
+						Ok(())
+					},
+					Err(e) => Err(format!("Bad value for charset: {} ({})", value, e))
+				}
+			},
+			"tone" => {
+				match ToneCurve::parse(value) {
+					Ok(c) => {
+						self.tone_curve = Some(c);
+						Ok(())
+					},
+					Err(e) => Err(format!("Bad value for tone: {} ({})", value, e))
+				}
+			},
+			_ => Err(format!("Unknown option: {}", key))
+		}
+	}
+
+	/**
+	 * Writes a value to the config file, replacing the existing line for that key if any
+	 */
+	pub fn save_value(&mut self, key: &str, value: &str) -> Result<(), String> {
+		let new_line = format!("{} = {}", key, value);
+		let mut found = false;
+		for line in self.file_lines.iter_mut() {
+			let is_match = match Config::parse_line(line) {
+				Some((k, _)) => k == key,
+				None => false
+			};
+			if is_match {
+				*line = new_line.clone();
+				found = true;
+			}
+		}
+		if ! found {
+			self.file_lines.push(new_line);
+		}
+
+		let path = match Config::path() {
+			Some(p) => p,
+			None => return Err("No home directory".to_string())
+		};
+		let mut text = self.file_lines.join("\n");
+		text.push('\n');
+		match File::create(&path) {
+			Ok(mut file) => match file.write_all(text.as_bytes()) {
+				Ok(_) => Ok(()),
+				Err(e) => Err(format!("{}", e))
+			},
+			Err(e) => Err(format!("{}", e))
+		}
+	}
+
+	/**
+	 * Returns key and value for lines of the form 'key = value';
+	 * blank lines and comments ('#') return None
+	 */
+	fn parse_line(line: &str) -> Option<(String, String)> {
+		let line = line.trim();
+		if line.len() == 0 || line.starts_with('#') {
+			return None;
+		}
+		match line.find('=') {
+			Some(i) => Some((line[..i].trim().to_string(), line[(i + 1)..].trim().to_string())),
+			None => None
+		}
+	}
+
+	fn path() -> Option<String> {
+		match env::var("HOME") {
+			Ok(home) => Some(format!("{}/{}", home, CONFIG_FILENAME)),
+			Err(_) => None
+		}
+	}
+
+	fn read_file() -> Option<String> {
+		let path = match Config::path() {
+			Some(p) => p,
+			None => return None
+		};
+		let mut text = String::new();
+		match File::open(&path) {
+			Ok(mut file) => match file.read_to_string(&mut text) {
+				Ok(_) => Some(text),
+				Err(_) => None
+			},
+			Err(_) => None
+		}
+	}
+
+	fn exit_with_usage(message: Option<String>) -> ! {
+		match message {
+			Some(s) => {
+				eprintln!("{}\n", s);
+				eprint!("{}", USAGE_TEXT);
+				process::exit(1);
+			},
+			None => {
+				// (asked for with --help)
+				print!("{}", USAGE_TEXT);
+				process::exit(0);
+			}
+		}
+	}
+}
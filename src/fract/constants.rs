@@ -3,11 +3,19 @@ use std;
 // a given terminal may not (probably won't) show every frame @ 60fps, but
 pub const TARGET_FPS: i32 = 60;   
 
-// rough estimate of terminal character a/r, which we can't rly know
+// rough estimate of terminal character a/r, which we can't rly know; 
+// only used when it isn't configured and can't be detected
 pub const CHARACTER_ASPECT_RATIO: f64 = 0.4;
 
+// calibration screen increments, as ratios
+pub const ASPECT_RATIO_INCREMENT: f64 = 0.02;
+pub const ASPECT_RATIO_FINE_INCREMENT: f64 = 0.004;
+
 pub static MANDELBROT_POI_TEXT: &'static str = include_str!("res/mandelbrot_pois.txt");
 pub static JULIA_COMPLEX_TEXT: &'static str = include_str!("res/julia_complex.txt");
+pub static NEWTON_POLYNOMIALS_TEXT: &'static str = include_str!("res/newton_polynomials.txt");
+pub static LYAPUNOV_SEQUENCES_TEXT: &'static str = include_str!("res/lyapunov_sequences.txt");
+pub static POINT_SYSTEMS_TEXT: &'static str = include_str!("res/point_systems.txt");
 
 pub const DEG: f64 = std::f64::consts::PI / 180.0;
 
@@ -18,4 +26,7 @@ pub const ROTATIONAL_VELOCITY_INCREMENT: f64 = 1.2 * DEG;
 pub const TARGET_COEF: f64 = 0.08;
 pub const FRICTION: f64 = 0.95;
 
+// supersampling cycles thru 1x1 (off), 2x2 ... up to this
+pub const MAX_SUPERSAMPLE_SIZE: usize = 4;
+
 pub const SHOW_DEBUG_TEXT: bool = false;
@@ -1,5 +1,8 @@
 extern crate num;
 use self::num::complex::{Complex, Complex64};
+use fract::newton::Polynomial;
+use fract::lyapunov::Sequence;
+use fract::attractor::PointSystem;
 
 
 pub type Three64 = (f64, f64, f64);
@@ -142,3 +145,94 @@ impl CoordList<Complex64> {
 		Some( Complex { re: re, im: im } )
 	}
 }
+
+impl CoordList<Polynomial> {
+
+	pub fn new(textfile: &str) -> CoordList<Polynomial> {
+		CoordList { coordlist: CoordList::parse_polynomials(textfile) }
+	}	
+
+	fn parse_polynomials(textfile: &str) -> [Polynomial; 10] {
+		
+		let default = Polynomial::parse("z^3 - 1").unwrap();
+		let mut coordlist = [default; 10];
+		let lines: Vec<&str> = textfile.lines().collect();
+		let mut count = 0;
+		for str in lines {
+			if str.trim().starts_with('#') {
+				continue;
+			}
+			match Polynomial::parse(&str) {
+				Ok(val) => {
+					coordlist[count] = val;
+					count += 1;
+					if count >= 10 {
+						break;
+					}
+				},
+				Err(_) => { }
+			}
+		}
+		coordlist
+	}
+}
+
+impl CoordList<Sequence> {
+
+	pub fn new(textfile: &str) -> CoordList<Sequence> {
+		CoordList { coordlist: CoordList::parse_sequences(textfile) }
+	}	
+
+	fn parse_sequences(textfile: &str) -> [Sequence; 10] {
+		
+		let default = Sequence::parse("AB").unwrap();
+		let mut coordlist = [default; 10];
+		let mut count = 0;
+		for str in textfile.lines() {
+			if str.trim().starts_with('#') {
+				continue;
+			}
+			match Sequence::parse(&str) {
+				Ok(val) => {
+					coordlist[count] = val;
+					count += 1;
+					if count >= 10 {
+						break;
+					}
+				},
+				Err(_) => { }
+			}
+		}
+		coordlist
+	}
+}
+
+impl CoordList<PointSystem> {
+
+	pub fn new(textfile: &str) -> CoordList<PointSystem> {
+		CoordList { coordlist: CoordList::parse_point_systems(textfile) }
+	}	
+
+	fn parse_point_systems(textfile: &str) -> [PointSystem; 10] {
+		
+		let default = PointSystem::parse("sierpinski").unwrap();
+		let mut coordlist = [default; 10];
+		let mut count = 0;
+		for str in textfile.lines() {
+			if str.trim().starts_with('#') {
+				continue;
+			}
+			match PointSystem::parse(&str) {
+				Ok(val) => {
+					coordlist[count] = val;
+					count += 1;
+					if count >= 10 {
+						break;
+					}
+				},
+				Err(_) => { }
+			}
+		}
+		coordlist
+	}
+}
@@ -0,0 +1,144 @@
+use std::u8;
+use leelib::matrix::Matrix;
+
+
+// 4x4 Bayer matrix
+const BAYER: [[u8; 4]; 4] = [
+	[ 0,  8,  2, 10],
+	[12,  4, 14,  6],
+	[ 3, 11,  1,  9],
+	[15,  7, 13,  5] ];
+
+// error diffusion: how far (in char indices) a cell's level has to move past the midpoint between its
+// previous char and the next one before the char changes, which keeps the pattern from shimmering
+// from frame to frame while the image is moving
+const HYSTERESIS: f64 = 0.25;
+
+
+/**
+ * How continuous char 'levels' (see `Asciifier::to_level()`) get quantized to char indices
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dither {
+	// plain truncation (the original behavior)
+	None,
+	// ordered dithering, whose pattern is fixed to the screen, so it's stable between frames
+	Bayer,
+	// error diffusion
+	FloydSteinberg,
+	Atkinson,
+}
+
+impl Dither {
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Dither::None => "off",
+			Dither::Bayer => "bayer 4x4",
+			Dither::FloydSteinberg => "floyd-steinberg",
+			Dither::Atkinson => "atkinson",
+		}
+	}
+
+	pub fn next(&self) -> Dither {
+		match *self {
+			Dither::None => Dither::Bayer,
+			Dither::Bayer => Dither::FloydSteinberg,
+			Dither::FloydSteinberg => Dither::Atkinson,
+			Dither::Atkinson => Dither::None,
+		}
+	}
+
+	/**
+	 * levels - continuous char indices, where char i covers levels i to i+1
+	 * num_chars - the length of the charset
+	 * index_matrix - gets written to; its existing values are taken to be the previous frame's
+	 * (unless past the charset; see `reset()`)
+	 */
+	pub fn write_indices(&self, levels: &Matrix<f64>, num_chars: usize, index_matrix: &mut Matrix<u8>) {
+		let max = (num_chars - 1) as f64;
+		match *self {
+			Dither::None => {
+				for y in 0..levels.height() {
+					for x in 0..levels.width() {
+						let i = levels.get(x, y).floor().max(0.0).min(max);
+						index_matrix.set(x, y, i as u8);
+					}
+				}
+			},
+			Dither::Bayer => {
+				for y in 0..levels.height() {
+					for x in 0..levels.width() {
+						// (a threshold in the range 0 to 1; a level halfway between two chars' centers gets each half the time)
+						let t = (BAYER[y % 4][x % 4] as f64 + 0.5) / 16.0;
+						let i = (levels.get(x, y) + 0.5 - t).floor().max(0.0).min(max);
+						index_matrix.set(x, y, i as u8);
+					}
+				}
+			},
+			Dither::FloydSteinberg => {
+				let weights = [(1, 0, 7.0 / 16.0), (-1, 1, 3.0 / 16.0), (0, 1, 5.0 / 16.0), (1, 1, 1.0 / 16.0)];
+				Dither::diffuse(levels, max, &weights, true, index_matrix);
+			},
+			Dither::Atkinson => {
+				// (only passes on 3/4 of the error)
+				let weights = [(1, 0, 1.0 / 8.0), (2, 0, 1.0 / 8.0), (-1, 1, 1.0 / 8.0), (0, 1, 1.0 / 8.0), (1, 1, 1.0 / 8.0), (0, 2, 1.0 / 8.0)];
+				Dither::diffuse(levels, max, &weights, false, index_matrix);
+			},
+		}
+	}
+
+	/**
+	 * Marks `index_matrix` as having no previous frame, for when the fractal matrix has been recalculated,
+	 * so that error diffusion doesn't hold onto chars which belonged to whatever was in each cell before
+	 */
+	pub fn reset(index_matrix: &mut Matrix<u8>) {
+		for y in 0..index_matrix.height() {
+			for x in 0..index_matrix.width() {
+				index_matrix.set(x, y, u8::MAX);
+			}
+		}
+	}
+
+	/**
+	 * weights - (x offset, y offset, ratio of the error); x offsets get mirrored on right-to-left rows
+	 * serpentine - whether to alternate the direction of each row
+	 */
+	fn diffuse(levels: &Matrix<f64>, max: f64, weights: &[(i32, i32, f64)], serpentine: bool, index_matrix: &mut Matrix<u8>) {
+
+		let w = levels.width();
+		let h = levels.height();
+		let mut error = Matrix::<f64>::new(w, h);
+
+		for y in 0..h {
+			let is_reversed = serpentine && y % 2 == 1;
+			for n in 0..w {
+				let x = if is_reversed { w - 1 - n } else { n };
+				let level = levels.get(x, y) + error.get(x, y);
+
+				// round to the nearest char center (char i's center being i + 0.5), unless still close to the previous char
+				let mut i = (level.floor()).max(0.0).min(max);
+				let prev = index_matrix.get(x, y) as f64;
+				if prev <= max && (level - (prev + 0.5)).abs() < 0.5 + HYSTERESIS {
+					i = prev;
+				}
+				index_matrix.set(x, y, i as u8);
+
+				// (levels past the centers of the lightest and heaviest chars have no error to pass on)
+				let e = level.max(0.5).min(max + 0.5) - (i + 0.5);
+				if e == 0.0 {
+					continue;
+				}
+				for &(dx, dy, ratio) in weights.iter() {
+					let dx = if is_reversed { -dx } else { dx };
+					let ex = x as i32 + dx;
+					let ey = y as i32 + dy;
+					if ex >= 0 && ey >= 0 && (ex as usize) < w && (ey as usize) < h {
+						let v = error.get(ex as usize, ey as usize) + e * ratio;
+						error.set(ex as usize, ey as usize, v);
+					}
+				}
+			}
+		}
+	}
+}
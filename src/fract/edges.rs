@@ -0,0 +1,112 @@
+use std::f64::consts::PI;
+use leelib::matrix::Matrix;
+
+
+// the gradient (as a ratio of the whole char ramp, per cell width) above which a cell counts as being on an edge
+const THRESHOLD: f64 = 0.2;
+
+// line angles (degrees from horizontal) past which an edge counts as diagonal, and then as steep, and then as vertical
+const DIAGONAL_ANGLE: f64 = 15.0;
+const STEEP_ANGLE: f64 = 65.0;
+const VERTICAL_ANGLE: f64 = 80.0;
+
+
+/**
+ * 'Static' class
+ * Finds the cells which lie on strong edges, and picks a directional glyph for each,
+ * so that boundaries get drawn as outlines rather than as blobs
+ */
+pub struct Edges;
+
+impl Edges {
+
+	/**
+	 * levels - continuous char indices (see `Asciifier::write_index_matrix()`)
+	 * num_chars - the length of the charset
+	 * element_ar - the width/height ratio of a cell, which the edges' angles depend on
+	 * edge_matrix - gets a glyph for each cell on an edge, and '\0' for the rest
+	 */
+	pub fn write(levels: &Matrix<f64>, num_chars: usize, element_ar: f64, edge_matrix: &mut Matrix<char>) {
+
+		let w = levels.width();
+		let h = levels.height();
+
+		// sobel, in ratios of the ramp per cell; gy is positive going down
+		let mut gradients = Matrix::<(f64, f64)>::new(w, h);
+		let mut magnitudes = Matrix::<f64>::new(w, h);
+		let get = |x: i32, y: i32| -> f64 {
+			let x = x.max(0).min(w as i32 - 1) as usize;
+			let y = y.max(0).min(h as i32 - 1) as usize;
+			levels.get(x, y) / num_chars as f64
+		};
+		for y in 0..h {
+			for x in 0..w {
+				let (x, y) = (x as i32, y as i32);
+				let gx = (get(x + 1, y - 1) + 2.0 * get(x + 1, y) + get(x + 1, y + 1)
+						- get(x - 1, y - 1) - 2.0 * get(x - 1, y) - get(x - 1, y + 1)) / 8.0;
+				let gy = (get(x - 1, y + 1) + 2.0 * get(x, y + 1) + get(x + 1, y + 1)
+						- get(x - 1, y - 1) - 2.0 * get(x, y - 1) - get(x + 1, y - 1)) / 8.0;
+				// (cells are taller than they are wide, so a vertical change is spread over more distance)
+				let gy = gy * element_ar;
+				gradients.set(x as usize, y as usize, (gx, gy));
+				magnitudes.set(x as usize, y as usize, (gx * gx + gy * gy).sqrt());
+			}
+		}
+
+		for y in 0..h {
+			for x in 0..w {
+				let m = magnitudes.get(x, y);
+				let (gx, gy) = gradients.get(x, y);
+				let char = if m > THRESHOLD && Edges::is_local_max(&magnitudes, x, y, gx / m, gy / m) {
+					Edges::glyph(gx, gy)
+				} else {
+					'\0'
+				};
+				edge_matrix.set(x, y, char);
+			}
+		}
+	}
+
+	/**
+	 * Non-maximum suppression: whether the cell's gradient is at least as strong as the cells on either side of it
+	 * (along the gradient's direction), which keeps the outlines one cell thick
+	 */
+	fn is_local_max(magnitudes: &Matrix<f64>, x: usize, y: usize, nx: f64, ny: f64) -> bool {
+		let dx = nx.round() as i32;
+		let dy = ny.round() as i32;
+		let m = magnitudes.get(x, y);
+		for &sign in [-1, 1].iter() {
+			let x2 = x as i32 + dx * sign;
+			let y2 = y as i32 + dy * sign;
+			if x2 >= 0 && y2 >= 0 && (x2 as usize) < magnitudes.width() && (y2 as usize) < magnitudes.height() {
+				if magnitudes.get(x2 as usize, y2 as usize) > m {
+					return false;
+				}
+			}
+		}
+		true
+	}
+
+	/**
+	 * gx, gy - the gradient, which points towards the heavier side (y going down)
+	 */
+	fn glyph(gx: f64, gy: f64) -> char {
+
+		// the edge runs perpendicular to the gradient; its angle is 0 to 180, counterclockwise from horizontal
+		let angle = (-gy).atan2(gx) * 180.0 / PI + 90.0;
+		let angle = ((angle % 180.0) + 180.0) % 180.0;
+		let from_horizontal = if angle > 90.0 { 180.0 - angle } else { angle };
+
+		if from_horizontal < DIAGONAL_ANGLE {
+			// '_' hugs the bottom of the cell, next to the heavier side below it
+			if gy > 0.0 { '_' } else { '-' }
+		} else if from_horizontal < STEEP_ANGLE {
+			if angle < 90.0 { '/' } else { '\\' }
+		} else if from_horizontal < VERTICAL_ANGLE {
+			// the outline of a heavier area to the right bulges to the left, and vice versa
+			if gx > 0.0 { '(' } else { ')' }
+		} else {
+			'|'
+		}
+	}
+}
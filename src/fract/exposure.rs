@@ -1,11 +1,42 @@
 use leelib::math;
 use leelib::matrix::Matrix;
+use fract::fractalcalc::FractalSpecs;
 
 
 pub struct ExposureInfo {
 	pub floor: usize,
 	pub ceil: usize,
-	pub bias: f64
+	pub bias: f64,
+	pub curve: Vec<f64>,  // only populated when using `ExposureMode::Equalize`
+}
+
+
+/**
+ * How auto-exposure decides which values get which chars
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExposureMode {
+	// floor, ceil, and a 'bias' which blends between a sqrt and a square curve (see `Mapping::Bias`)
+	Heuristic,
+	// histogram equalization, so that each char covers roughly the same number of cells
+	Equalize,
+}
+
+impl ExposureMode {
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			ExposureMode::Heuristic => "heuristic",
+			ExposureMode::Equalize => "equalize",
+		}
+	}
+
+	pub fn next(&self) -> ExposureMode {
+		match *self {
+			ExposureMode::Heuristic => ExposureMode::Equalize,
+			ExposureMode::Equalize => ExposureMode::Heuristic,
+		}
+	}
 }
 
 
@@ -18,6 +49,19 @@ pub struct ExposureUtil;
 
 impl ExposureUtil {
 	
+	/**
+	 * Uses whichever of the below suits the kind of values that `specs` produces
+	 */
+	pub fn calc_for_specs(matrix: &Matrix<u16>, specs: &FractalSpecs) -> ExposureInfo {
+		if specs.uses_distance() {
+			ExposureUtil::calc_distance(matrix, specs.max_val, 0.25)
+		} else if specs.is_signed() {
+			ExposureUtil::calc_signed(matrix, specs.max_val, 0.010)
+		} else {
+			ExposureUtil::calc(matrix, specs.max_val, 0.040, 0.010)
+		}
+	}
+
 	/**
 	 * max_val - the max value of anything in the matrix; used to create 'histogram'
 	 * lower/upper_thresh_ratio - the ratio of the amount of upper and lower values to discard when calculating the range
@@ -27,14 +71,112 @@ impl ExposureUtil {
 	pub fn calc(matrix: &Matrix<u16>, max_val: u16, lower_thresh_ratio: f64, upper_thresh_ratio: f64) -> ExposureInfo {
 
 		// count the values in `matrix`
-		let mut histogram = vec!(0u16; (max_val + 1) as usize);
+		let mut histogram = vec!(0u16; max_val as usize + 1);
 		for val in matrix {
 			histogram[val as usize] += 1;
 		}
 
 		let range = ExposureUtil::get_range(&histogram, &matrix, lower_thresh_ratio, upper_thresh_ratio);
 		let bias = ExposureUtil::calc_bias(&histogram, range.0, range.1);
-		ExposureInfo { floor: range.0, ceil: range.1, bias: bias }
+		ExposureInfo { floor: range.0, ceil: range.1, bias: bias, curve: Vec::new() }
+	}
+
+	/**
+	 * Histogram equalization.
+	 * 
+	 * Returns a lookup table, indexed by value (0 to max_val), of ratios (0 to 1) which take the place of 
+	 * the asciifier's mapping curve (see `Asciifier::write_index_matrix()`). The ratios come from the 
+	 * cumulative distribution of the values in range `floor` to `ceil`, normalized so that the lowest value 
+	 * which occurs gets 0 and `ceil` gets 1, so that each char ends up covering about the same number of cells.
+	 */
+	pub fn calc_equalized(matrix: &Matrix<u16>, max_val: u16, floor: usize, ceil: usize) -> Vec<f64> {
+
+		let mut histogram = vec!(0u32; max_val as usize + 1);
+		for val in matrix {
+			histogram[val as usize] += 1;
+		}
+
+		let ceil = ceil.min(max_val as usize);
+		let floor = floor.min(ceil);
+		let total: u32 = histogram[floor..(ceil + 1)].iter().sum();
+
+		let mut curve = vec!(0.0; max_val as usize + 1);
+		let mut cdf_min = None;
+		let mut sum = 0;
+		for i in floor..(ceil + 1) {
+			sum += histogram[i];
+			if cdf_min.is_none() && sum > 0 {
+				cdf_min = Some(sum);
+			}
+			match cdf_min {
+				Some(min) if total > min => curve[i] = (sum - min) as f64 / (total - min) as f64,
+				_ => {}
+			}
+		}
+		for i in (ceil + 1)..curve.len() {
+			curve[i] = 1.0;
+		}
+		curve
+	}
+	
+	/**
+	 * Exposure for distance-estimate values (see `RenderMode::Distance`), where max_val means 
+	 * 'on the boundary or inside' and lower values mean 'further away'. 
+	 *
+	 * The ceiling is always max_val. The floor is set so that only the closest `coverage_ratio` 
+	 * of the exterior cells fall within the range, which keeps the lines thin regardless of 
+	 * how much of the boundary is on screen. 
+	 */
+	pub fn calc_distance(matrix: &Matrix<u16>, max_val: u16, coverage_ratio: f64) -> ExposureInfo {
+
+		let mut histogram = vec!(0u32; max_val as usize + 1);
+		for val in matrix {
+			histogram[val as usize] += 1;
+		}
+
+		let num_exterior: u32 = histogram[0..(max_val as usize)].iter().sum();
+		let thresh = num_exterior as f64 * coverage_ratio;
+		
+		let mut floor = 0;
+		let mut sum = 0;
+		for i in (0..(max_val as usize)).rev() {
+			sum += histogram[i];
+			if sum as f64 > thresh {
+				floor = i;
+				break;
+			}
+		}
+		
+		ExposureInfo { floor: floor, ceil: max_val as usize, bias: 0.0, curve: Vec::new() }
+	}
+	
+	/**
+	 * Exposure for signed values (see `FractalSpecs::is_signed()`), where half of max_val is the zero point.
+	 *
+	 * The range stays centered on the zero point, and is made just wide enough to contain  
+	 * all but the `outlier_ratio` of values which lie furthest from it.
+	 */
+	pub fn calc_signed(matrix: &Matrix<u16>, max_val: u16, outlier_ratio: f64) -> ExposureInfo {
+
+		let mid = max_val as usize / 2;
+		let mut histogram = vec!(0u32; mid + 1);  // by distance from the zero point
+		for val in matrix {
+			let d = (val as i32 - mid as i32).abs() as usize;
+			histogram[d.min(mid)] += 1;
+		}
+
+		let thresh = (matrix.width() * matrix.height()) as f64 * outlier_ratio;
+		let mut extent = mid;
+		let mut sum = 0;
+		for i in (0..(mid + 1)).rev() {
+			sum += histogram[i];
+			if sum as f64 > thresh {
+				extent = i.max(1);
+				break;
+			}
+		}
+
+		ExposureInfo { floor: mid - extent, ceil: mid + extent, bias: 0.0, curve: Vec::new() }
 	}
 	
 	/**
@@ -142,3 +284,65 @@ impl ExposureUtil {
 		return (a + b + c) / 3.0;
 	}
 }
+
+
+/**
+ * Eases an equalization curve (see `ExposureUtil::calc_equalized()`) towards its target, 
+ * the way the floor and ceil animators do for the heuristic exposure
+ */
+pub struct CurveAnimator {
+	pub value: Vec<f64>,  // empty when not in use
+	target: Vec<f64>,
+	release: bool,
+}
+
+impl CurveAnimator {
+
+	pub fn new() -> Self {
+		CurveAnimator { value: Vec::new(), target: Vec::new(), release: false }
+	}
+
+	/**
+	 * start - the curve to ease from, if the animator isn't already in use (or if the length has changed)
+	 */
+	pub fn set_target(&mut self, target: Vec<f64>, start: Vec<f64>) {
+		if self.value.len() != target.len() {
+			self.value = if start.len() == target.len() { start } else { target.clone() };
+		}
+		self.target = target;
+		self.release = false;
+	}
+
+	/**
+	 * Eases back to `target` (the curve equivalent of the asciifier's own mapping), and then stops being in use
+	 */
+	pub fn release(&mut self, target: Vec<f64>) {
+		if self.value.len() != target.len() {
+			self.value = Vec::new();
+			return;
+		}
+		self.target = target;
+		self.release = true;
+	}
+
+	/**
+	 * Returns true if the value changed
+	 */
+	pub fn update(&mut self, coefficient: f64, epsilon: f64) -> bool {
+
+		let mut changed = false;
+		for i in 0..self.value.len() {
+			let d = self.target[i] - self.value[i];
+			if d != 0.0 {
+				self.value[i] = if d.abs() < epsilon { self.target[i] } else { self.value[i] + d * coefficient };
+				changed = true;
+			}
+		}
+		if self.release && ! changed {
+			self.value = Vec::new();
+			self.release = false;
+			changed = true;
+		}
+		changed
+	}
+}
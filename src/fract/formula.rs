@@ -0,0 +1,527 @@
+extern crate num;
+
+use self::num::complex::{Complex, Complex64};
+
+
+pub const MAX_OPS: usize = 48;
+const MAX_STACK: usize = 16;
+
+// bounds the parser's recursion, since '(' and unary '+' emit no instructions of their own
+const MAX_DEPTH: usize = 64;
+
+// integer exponents up to this get done by repeated multiplication
+const MAX_INT_POWER: i32 = 16;
+
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Func {
+	Sin, Cos, Tan, Sinh, Cosh, Tanh, Exp, Ln, Sqrt, Abs, Conj,
+}
+
+impl Func {
+
+	fn from_name(name: &str) -> Option<Func> {
+		match name {
+			"sin" => Some(Func::Sin),
+			"cos" => Some(Func::Cos),
+			"tan" => Some(Func::Tan),
+			"sinh" => Some(Func::Sinh),
+			"cosh" => Some(Func::Cosh),
+			"tanh" => Some(Func::Tanh),
+			"exp" => Some(Func::Exp),
+			"ln" | "log" => Some(Func::Ln),
+			"sqrt" => Some(Func::Sqrt),
+			"abs" => Some(Func::Abs),
+			"conj" => Some(Func::Conj),
+			_ => None
+		}
+	}
+
+	fn apply(&self, z: Complex64) -> Complex64 {
+		match *self {
+			Func::Sin => z.sin(),
+			Func::Cos => z.cos(),
+			Func::Tan => z.tan(),
+			Func::Sinh => z.sinh(),
+			Func::Cosh => z.cosh(),
+			Func::Tanh => z.tanh(),
+			Func::Exp => z.exp(),
+			Func::Ln => z.ln(),
+			Func::Sqrt => z.sqrt(),
+			Func::Abs => Complex { re: z.re.abs(), im: z.im.abs() },  // 'burning ship'-style
+			Func::Conj => z.conj(),
+		}
+	}
+}
+
+
+/**
+ * Instructions for the stack machine that evaluates a `Formula`
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Op {
+	Z, C,
+	Const(Complex64),
+	Add, Sub, Mul, Div, Neg,
+	PowInt(i32),
+	Pow,
+	Func(Func),
+}
+
+
+/**
+ * An iteration formula in terms of z and c (eg, "z^3 + c", "sin(z)*c", "z^2 + c + 0.1/z"),
+ * compiled into a fixed-size sequence of stack machine instructions.
+ *
+ * Fixed-size so that it can live in `FractalSpecs`, which is `Copy`.
+ */
+#[derive(Clone, Copy)]
+pub struct Formula {
+	ops: [Op; MAX_OPS],
+	len: usize,
+}
+
+// (only the instructions in use count; the parser can leave stale ones past `len`)
+impl PartialEq for Formula {
+	fn eq(&self, other: &Formula) -> bool {
+		self.ops[..self.len] == other.ops[..other.len]
+	}
+}
+
+impl Formula {
+
+	/**
+	 * Returns an error string which includes the (1-based) position of the problem
+	 */
+	pub fn parse(s: &str) -> Result<Formula, String> {
+		let tokens = match Formula::tokenize(s) {
+			Ok(t) => t,
+			Err(e) => return Err(e)
+		};
+		let mut parser = Parser { tokens: tokens, index: 0, depth: 0, formula: Formula { ops: [Op::Z; MAX_OPS], len: 0 } };
+		match parser.parse_expression() {
+			Err(e) => return Err(e),
+			_ => {}
+		}
+		if parser.index < parser.tokens.len() {
+			return Err(parser.error("Unexpected"));
+		}
+
+		let formula = parser.formula;
+		match formula.max_stack_depth() {
+			Some(depth) if depth <= MAX_STACK => Ok(formula),
+			_ => Err("Formula is too deeply nested".to_string())
+		}
+	}
+
+	/**
+	 * Evaluates the formula for the given z and c
+	 */
+	pub fn eval(&self, z: Complex64, c: Complex64) -> Complex64 {
+		let mut stack = [Complex { re: 0.0, im: 0.0 }; MAX_STACK];
+		let mut n = 0;
+		for i in 0..self.len {
+			match self.ops[i] {
+				Op::Z => { stack[n] = z; n += 1; },
+				Op::C => { stack[n] = c; n += 1; },
+				Op::Const(k) => { stack[n] = k; n += 1; },
+				Op::Neg => { stack[n - 1] = -stack[n - 1]; },
+				Op::PowInt(p) => { stack[n - 1] = Formula::pow_int(stack[n - 1], p); },
+				Op::Func(f) => { stack[n - 1] = f.apply(stack[n - 1]); },
+				op => {
+					let b = stack[n - 1];
+					let a = stack[n - 2];
+					n -= 1;
+					stack[n - 1] = match op {
+						Op::Add => a + b,
+						Op::Sub => a - b,
+						Op::Mul => a * b,
+						Op::Div => a / b,
+						_ => a.powc(b),  // Op::Pow
+					};
+				}
+			}
+		}
+		stack[0]
+	}
+
+	fn pow_int(z: Complex64, p: i32) -> Complex64 {
+		let mut result = Complex { re: 1.0, im: 0.0 };
+		for _ in 0..p.abs() {
+			result = result * z;
+		}
+		if p < 0 { result.inv() } else { result }
+	}
+
+	/**
+	 * Returns None if the instructions don't leave exactly one value on the stack
+	 */
+	fn max_stack_depth(&self) -> Option<usize> {
+		let mut n: i32 = 0;
+		let mut max = 0;
+		for i in 0..self.len {
+			n += match self.ops[i] {
+				Op::Z | Op::C | Op::Const(_) => 1,
+				Op::Neg | Op::PowInt(_) | Op::Func(_) => 0,
+				_ => -1
+			};
+			if n < 1 {
+				return None;
+			}
+			if n as usize > max {
+				max = n as usize;
+			}
+		}
+		if n == 1 { Some(max) } else { None }
+	}
+
+	fn tokenize(s: &str) -> Result<Vec<(Token, usize)>, String> {
+		let chars: Vec<char> = s.chars().collect();
+		let mut tokens = Vec::new();
+		let mut i = 0;
+		while i < chars.len() {
+			let ch = chars[i];
+			let pos = i + 1;
+			if ch.is_whitespace() {
+				i += 1;
+				continue;
+			}
+			if ch.is_digit(10) || ch == '.' {
+				let start = i;
+				while i < chars.len() && (chars[i].is_digit(10) || chars[i] == '.') {
+					i += 1;
+				}
+				// scientific notation
+				if i + 1 < chars.len() && chars[i] == 'e' && (chars[i + 1].is_digit(10) || chars[i + 1] == '-') {
+					i += 2;
+					while i < chars.len() && chars[i].is_digit(10) {
+						i += 1;
+					}
+				}
+				let text: String = chars[start..i].iter().cloned().collect();
+				match text.parse::<f64>() {
+					Ok(v) => tokens.push((Token::Number(v), pos)),
+					Err(_) => return Err(format!("Bad number '{}' at {}", text, pos))
+				}
+				continue;
+			}
+			if ch.is_alphabetic() {
+				let start = i;
+				while i < chars.len() && chars[i].is_alphanumeric() {
+					i += 1;
+				}
+				let text: String = chars[start..i].iter().cloned().collect();
+				if Func::from_name(&text).is_none() && text.chars().all(|ch| ch == 'z' || ch == 'c' || ch == 'i') {
+					// implicit multiplication of variables, as in "zc" 
+					for (j, ch) in text.chars().enumerate() {
+						tokens.push((Token::Name(ch.to_string()), pos + j));
+					}
+				} else {
+					tokens.push((Token::Name(text), pos));
+				}
+				continue;
+			}
+			let token = match ch {
+				'+' => Token::Plus,
+				'-' => Token::Minus,
+				'*' => Token::Star,
+				'/' => Token::Slash,
+				'^' => Token::Caret,
+				'(' => Token::LeftParen,
+				')' => Token::RightParen,
+				_ => return Err(format!("Unexpected '{}' at {}", ch, pos))
+			};
+			tokens.push((token, pos));
+			i += 1;
+		}
+		if tokens.len() == 0 {
+			return Err("Empty formula".to_string());
+		}
+		Ok(tokens)
+	}
+}
+
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+	Number(f64),
+	Name(String),
+	Plus, Minus, Star, Slash, Caret, LeftParen, RightParen,
+}
+
+
+/**
+ * Recursive descent parser, which writes instructions into `formula` as it goes (ie, in postfix order).
+ *
+ *     expression  = term { ("+" | "-") term }
+ *     term        = unary { ["*" | "/"] unary }     (no operator means multiplication, as in "2z")
+ *     unary       = "-" unary | power
+ *     power       = atom [ "^" unary ]
+ *     atom        = number ["i"] | "i" | "z" | "c" | function "(" expression ")" | "(" expression ")"
+ */
+struct Parser {
+	tokens: Vec<(Token, usize)>,
+	index: usize,
+	depth: usize,
+	formula: Formula,
+}
+
+impl Parser {
+
+	fn peek(&self) -> Option<Token> {
+		if self.index < self.tokens.len() {
+			Some(self.tokens[self.index].0.clone())
+		} else {
+			None
+		}
+	}
+
+	fn error(&self, message: &str) -> String {
+		if self.index < self.tokens.len() {
+			let (ref token, pos) = self.tokens[self.index];
+			let text = match *token {
+				Token::Number(v) => format!("{}", v),
+				Token::Name(ref s) => s.clone(),
+				Token::Plus => "+".to_string(),
+				Token::Minus => "-".to_string(),
+				Token::Star => "*".to_string(),
+				Token::Slash => "/".to_string(),
+				Token::Caret => "^".to_string(),
+				Token::LeftParen => "(".to_string(),
+				Token::RightParen => ")".to_string(),
+			};
+			format!("{} '{}' at {}", message, text, pos)
+		} else {
+			format!("{} end of formula", message)
+		}
+	}
+
+	fn emit(&mut self, op: Op) -> Result<(), String> {
+		if self.formula.len >= MAX_OPS {
+			return Err("Formula is too long".to_string());
+		}
+		self.formula.ops[self.formula.len] = op;
+		self.formula.len += 1;
+		Ok(())
+	}
+
+	fn parse_expression(&mut self) -> Result<(), String> {
+		match self.parse_term() { Err(e) => return Err(e), _ => {} }
+		loop {
+			let op = match self.peek() {
+				Some(Token::Plus) => Op::Add,
+				Some(Token::Minus) => Op::Sub,
+				_ => return Ok(())
+			};
+			self.index += 1;
+			match self.parse_term() { Err(e) => return Err(e), _ => {} }
+			match self.emit(op) { Err(e) => return Err(e), _ => {} }
+		}
+	}
+
+	fn parse_term(&mut self) -> Result<(), String> {
+		match self.parse_unary() { Err(e) => return Err(e), _ => {} }
+		loop {
+			let op = match self.peek() {
+				Some(Token::Star) => { self.index += 1; Op::Mul },
+				Some(Token::Slash) => { self.index += 1; Op::Div },
+				// implicit multiplication
+				Some(Token::Number(_)) | Some(Token::Name(_)) | Some(Token::LeftParen) => Op::Mul,
+				_ => return Ok(())
+			};
+			match self.parse_unary() { Err(e) => return Err(e), _ => {} }
+			match self.emit(op) { Err(e) => return Err(e), _ => {} }
+		}
+	}
+
+	fn parse_unary(&mut self) -> Result<(), String> {
+		// every level of parentheses and every unary sign passes through here
+		if self.depth >= MAX_DEPTH {
+			return Err("Formula is too deeply nested".to_string());
+		}
+		self.depth += 1;
+		let result = self.parse_unary_inner();
+		self.depth -= 1;
+		result
+	}
+
+	fn parse_unary_inner(&mut self) -> Result<(), String> {
+		match self.peek() {
+			Some(Token::Minus) => {
+				self.index += 1;
+				match self.parse_unary() { Err(e) => return Err(e), _ => {} }
+				self.emit(Op::Neg)
+			},
+			Some(Token::Plus) => {
+				self.index += 1;
+				self.parse_unary()
+			},
+			_ => self.parse_power()
+		}
+	}
+
+	fn parse_power(&mut self) -> Result<(), String> {
+		match self.parse_atom() { Err(e) => return Err(e), _ => {} }
+		if self.peek() != Some(Token::Caret) {
+			return Ok(());
+		}
+		self.index += 1;
+
+		let start = self.formula.len;
+		match self.parse_unary() { Err(e) => return Err(e), _ => {} }
+
+		// a constant integer exponent becomes a single, cheaper instruction
+		if self.formula.len == start + 1 {
+			match self.formula.ops[start] {
+				Op::Const(k) if k.im == 0.0 && k.re == k.re.round() && k.re.abs() <= MAX_INT_POWER as f64 => {
+					self.formula.len = start;
+					return self.emit(Op::PowInt(k.re as i32));
+				},
+				_ => {}
+			}
+		} else if self.formula.len == start + 2 {
+			match (self.formula.ops[start], self.formula.ops[start + 1]) {
+				(Op::Const(k), Op::Neg) if k.im == 0.0 && k.re == k.re.round() && k.re.abs() <= MAX_INT_POWER as f64 => {
+					self.formula.len = start;
+					return self.emit(Op::PowInt(-(k.re as i32)));
+				},
+				_ => {}
+			}
+		}
+		self.emit(Op::Pow)
+	}
+
+	fn parse_atom(&mut self) -> Result<(), String> {
+		let token = match self.peek() {
+			Some(t) => t,
+			None => return Err(self.error("Unexpected"))
+		};
+		match token {
+			Token::Number(v) => {
+				self.index += 1;
+				// imaginary literal, as in "0.5i"
+				if self.peek() == Some(Token::Name("i".to_string())) {
+					self.index += 1;
+					return self.emit(Op::Const(Complex { re: 0.0, im: v }));
+				}
+				self.emit(Op::Const(Complex { re: v, im: 0.0 }))
+			},
+			Token::Name(name) => {
+				match &name[..] {
+					"z" => { self.index += 1; self.emit(Op::Z) },
+					"c" => { self.index += 1; self.emit(Op::C) },
+					"i" => { self.index += 1; self.emit(Op::Const(Complex { re: 0.0, im: 1.0 })) },
+					_ => {
+						let f = match Func::from_name(&name) {
+							Some(f) => f,
+							None => return Err(self.error("Unknown name"))
+						};
+						self.index += 1;
+						if self.peek() != Some(Token::LeftParen) {
+							return Err(self.error("Expected '(' instead of"));
+						}
+						self.index += 1;
+						match self.parse_expression() { Err(e) => return Err(e), _ => {} }
+						if self.peek() != Some(Token::RightParen) {
+							return Err(self.error("Expected ')' instead of"));
+						}
+						self.index += 1;
+						self.emit(Op::Func(f))
+					}
+				}
+			},
+			Token::LeftParen => {
+				self.index += 1;
+				match self.parse_expression() { Err(e) => return Err(e), _ => {} }
+				if self.peek() != Some(Token::RightParen) {
+					return Err(self.error("Expected ')' instead of"));
+				}
+				self.index += 1;
+				Ok(())
+			},
+			_ => Err(self.error("Unexpected"))
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_evals(s: &str, z: Complex64, c: Complex64, expected: Complex64) {
+		let f = Formula::parse(s).unwrap();
+		let v = f.eval(z, c);
+		assert!((v - expected).norm() < 1e-9, "{}: got {}, expected {}", s, v, expected);
+	}
+
+	fn ops(s: &str) -> Vec<Op> {
+		let f = Formula::parse(s).unwrap();
+		f.ops[..f.len].to_vec()
+	}
+
+	#[test]
+	fn precedence() {
+		let z = Complex { re: 0.3, im: -0.7 };
+		let c = Complex { re: -0.8, im: 0.156 };
+		let one = Complex { re: 1.0, im: 0.0 };
+		assert_evals("z^2 + c", z, c, z * z + c);
+		assert_evals("c + 2 * z", z, c, c + z * 2.0);
+		assert_evals("z - c - 1", z, c, z - c - one);
+		assert_evals("z / c / 2", z, c, z / c / 2.0);
+		assert_evals("(z + 1) * (z - 1)", z, c, (z + one) * (z - one));
+		assert_evals("2z^3 + zc", z, c, z * z * z * 2.0 + z * c);
+		assert_evals("z^2^2", z, c, z * z * z * z);
+		assert_evals("sin(z) * c + 0.5i", z, c, z.sin() * c + Complex { re: 0.0, im: 0.5 });
+	}
+
+	#[test]
+	fn unary_minus() {
+		let z = Complex { re: 0.3, im: -0.7 };
+		let c = Complex { re: -0.8, im: 0.156 };
+		assert_evals("-z^2 + c", z, c, -(z * z) + c);
+		assert_evals("c - -z", z, c, c + z);
+		assert_evals("-(z + c)", z, c, -(z + c));
+		assert_evals("z^-2", z, c, (z * z).inv());
+	}
+
+	#[test]
+	fn pow_int_folding() {
+		assert_eq!(ops("z^2"), vec![Op::Z, Op::PowInt(2)]);
+		assert_eq!(ops("z^-3"), vec![Op::Z, Op::PowInt(-3)]);
+		assert_eq!(ops("z^2.5").last(), Some(&Op::Pow));
+		assert_eq!(ops("z^c").last(), Some(&Op::Pow));
+		assert_eq!(ops("z^17").last(), Some(&Op::Pow));
+
+		let z = Complex { re: 0.3, im: -0.7 };
+		let c = Complex { re: 0.0, im: 0.0 };
+		assert_evals("z^2.5", z, c, z.powf(2.5));
+	}
+
+	#[test]
+	fn limits() {
+		// 25 z's and 24 adds is one instruction too many
+		let long: Vec<&str> = (0..25).map(|_| "z").collect();
+		assert!(Formula::parse(&long.join("+")).is_err());
+		let ok: Vec<&str> = (0..24).map(|_| "z").collect();
+		assert!(Formula::parse(&ok.join("+")).is_ok());
+
+		// z+(z+(z+...)) needs one stack slot per level
+		let nested = |n: usize| "z+(".repeat(n - 1) + "z" + &")".repeat(n - 1);
+		assert!(Formula::parse(&nested(MAX_STACK)).is_ok());
+		assert!(Formula::parse(&nested(MAX_STACK + 1)).is_err());
+
+		// recursion is bounded even where no instructions get emitted
+		assert!(Formula::parse(&("(".repeat(100000) + "z" + &")".repeat(100000))).is_err());
+		assert!(Formula::parse(&("+".repeat(100000) + "z")).is_err());
+		assert!(Formula::parse(&("-".repeat(100000) + "z")).is_err());
+		assert!(Formula::parse(&("+".repeat(MAX_DEPTH - 1) + "z")).is_ok());
+	}
+
+	#[test]
+	fn errors() {
+		for s in ["", "z +", "(z", "z)", "foo(z)", "sin z", "z $ c", "1..2"].iter() {
+			assert!(Formula::parse(s).is_err(), "{}", s);
+		}
+	}
+}
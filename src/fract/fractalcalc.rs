@@ -1,6 +1,7 @@
 extern crate num;
 extern crate num_cpus;
 
+use std::cmp;
 use std::thread;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
@@ -8,26 +9,143 @@ use self::num::complex::{Complex, Complex64};
 use self::num::traits::Float;
 use leelib::vector2::Vector2f;
 use leelib::matrix::Matrix;
+use fract::coloring::Coloring;
+use fract::newton::Polynomial;
+use fract::formula::Formula;
+use fract::buddhabrot::BuddhaMode;
+use fract::lyapunov::Sequence;
+use fract::attractor::PointSystem;
+use fract::asciifier::Mapping;
 
 
 const DEFAULT_MANDELBROT_WIDTH: f64 = 4.0;
 const DEFAULT_JULIA_WIDTH: f64 = 4.0; 
+const DEFAULT_NEWTON_WIDTH: f64 = 4.0; 
+const DEFAULT_BUDDHABROT_WIDTH: f64 = 3.5; 
+const DEFAULT_LYAPUNOV_WIDTH: f64 = 2.0; 
+
+// custom formulas: escape radius (larger than usual, for the sake of things like 'sin(z)*c')
+const FORMULA_BAILOUT: f64 = 10.0;
+
+// lyapunov: iterations of the logistic map done before and during the exponent's calculation,
+// and the value per unit of exponent (away from the zero point, which is half of max_val)
+const LYAPUNOV_WARMUP: usize = 50;
+const LYAPUNOV_ITERATIONS: usize = 200;
+const LYAPUNOV_SCALE: f64 = 250.0;
+
+// newton: distance from a root at which a point counts as having converged 
+const NEWTON_EPSILON: f64 = 1e-6;
+
+// distance estimation: escape radius (large, for a more accurate estimate), 
+// and the distance (as log2 of cells) past which cells are treated as being 'far away'
+const DE_BAILOUT: f64 = 1000.0;
+const DE_RANGE_LOG2: f64 = 4.0;
+
+// distance estimation + iteration band: how much of the value range the band takes up
+const DE_BAND_WEIGHT: f64 = 0.5;
 
 
 /**
  *
  */
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum FractalType {
 	Mandelbrot, 
-	Julia(Complex64)
+	Julia(Complex64),
+	Newton(Polynomial),
+	// not escape-time; see `Buddhabrot`
+	Buddhabrot(BuddhaMode),
+	// lyapunov exponent of the logistic map, where x and y are the growth rates 'a' and 'b'
+	Lyapunov(Sequence),
+	// not escape-time either; strange attractors and IFS's, see `Attractor`
+	PointCloud(PointSystem),
+}
+
+impl FractalType {
+
+	/**
+	 * Identifies the type (but not its parameters) in location strings (see `Location`)
+	 */
+	pub fn id(&self) -> &'static str {
+		match *self {
+			FractalType::Mandelbrot => "mandelbrot",
+			FractalType::Julia(..) => "julia",
+			FractalType::Newton(..) => "newton",
+			FractalType::Buddhabrot(..) => "buddhabrot",
+			FractalType::Lyapunov(..) => "lyapunov",
+			FractalType::PointCloud(..) => "pointcloud",
+		}
+	}
+}
+
+
+/**
+ * What the values in the fractal matrix represent
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum RenderMode {
+	// escape-time iteration count
+	Escape,
+	// exterior distance estimate, as 'closeness' to the set's boundary (max_val at the boundary or inside the set) 
+	Distance,
+	// the above, combined with a (log-scaled) escape-time band
+	DistanceBand,
+}
+
+impl RenderMode {
+	pub fn uses_distance(&self) -> bool {
+		match *self {
+			RenderMode::Escape => false,
+			_ => true
+		}
+	}
+}
+
+
+/**
+ * Where the sub-samples of a supersampled cell lie
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum SamplePattern {
+	Grid,
+	Jitter,  // grid, with each sample randomly offset within its sub-cell (but the same way every frame) 
+}
+
+/**
+ * How the sub-samples of a supersampled cell get combined into the cell's value
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum SampleReduce {
+	Average,
+	Max,
+	Min,
+}
+
+/**
+ * Supersampling settings; `size` is the number of sub-samples per cell in each dimension (1 for none) 
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Supersample {
+	pub size: usize,
+	pub pattern: SamplePattern,
+	pub reduce: SampleReduce,
+}
+
+impl Supersample {
+	pub fn new() -> Self {
+		Supersample { size: 1, pattern: SamplePattern::Jitter, reduce: SampleReduce::Average }
+	}
+	
+	pub fn is_on(&self) -> bool {
+		self.size > 1
+	}
 }
 
 
 /**
  * Simple value object, passed around for use with FractalCalc methods
  */
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub struct FractalSpecs {
 	pub fractal_type: FractalType,
 	pub max_val: u16,
@@ -36,9 +154,59 @@ pub struct FractalSpecs {
 	pub element_ar: f64,
 	pub num_threads: usize,
 	pub use_multi_threads: bool,
+	pub use_mariani_silver: bool,
+	pub supersample: Supersample,
+	pub render_mode: RenderMode,
+	pub coloring: Coloring,
+	// replaces 'z^2 + c' for the mandelbrot and julia types; 
+	// when set, `render_mode` and `coloring` don't apply
+	pub formula: Option<Formula>,
 }
 
 impl FractalSpecs {
+
+	/**
+	 * Whether cell values are distance estimates, which only the built-in mandelbrot and julia formulas support
+	 */
+	pub fn uses_distance(&self) -> bool {
+		self.render_mode.uses_distance() && self.supports_render_options()
+	}
+
+	/**
+	 * Whether `render_mode` and `coloring` apply 
+	 */
+	pub fn supports_render_options(&self) -> bool {
+		match self.fractal_type {
+			FractalType::Newton(..) | FractalType::Buddhabrot(..) | FractalType::Lyapunov(..) | 
+					FractalType::PointCloud(..) => false,
+			_ => self.formula.is_none()
+		}
+	}
+
+	/**
+	 * Whether values are signed, with half of max_val being the zero point 
+	 * (see `Mapping::Signed`)
+	 */
+	pub fn is_signed(&self) -> bool {
+		match self.fractal_type {
+			FractalType::Lyapunov(..) => true,
+			_ => false
+		}
+	}
+
+	/**
+	 * How the asciifier should map values to chars
+	 */
+	pub fn mapping(&self) -> Mapping {
+		if self.uses_distance() { 
+			Mapping::Lines 
+		} else if self.is_signed() {
+			Mapping::Signed
+		} else { 
+			Mapping::Bias 
+		}
+	}
+
 	pub fn new_mandelbrot_with_defaults(element_ar: f64) -> Self {
 		FractalSpecs {
 			fractal_type: FractalType::Mandelbrot,
@@ -47,7 +215,12 @@ impl FractalSpecs {
 			default_center: Vector2f::new(0.0, 0.0), 
 			element_ar: element_ar,
 			num_threads: num_cpus::get() as usize,
-			use_multi_threads: true 
+			use_multi_threads: true,
+			use_mariani_silver: false,
+			supersample: Supersample::new(),
+			render_mode: RenderMode::Escape,
+			coloring: Coloring::Escape,
+			formula: None,
 		}
 	}
 	
@@ -60,12 +233,150 @@ impl FractalSpecs {
 			default_center: Vector2f::new(0.0, 0.0), 
 			element_ar: element_ar,
 			num_threads: num_cpus::get() as usize,
-			use_multi_threads: true 
+			use_multi_threads: true,
+			use_mariani_silver: false,
+			supersample: Supersample::new(),
+			render_mode: RenderMode::Escape,
+			coloring: Coloring::Escape,
+			formula: None,
+		}
+	}
+
+	pub fn new_newton(polynomial: Polynomial, element_ar: f64) -> Self {
+		FractalSpecs {
+			fractal_type: FractalType::Newton(polynomial),
+
+			max_val: 100,
+			default_width: DEFAULT_NEWTON_WIDTH,
+			default_center: Vector2f::new(0.0, 0.0), 
+			element_ar: element_ar,
+			num_threads: num_cpus::get() as usize,
+			use_multi_threads: true,
+			use_mariani_silver: false,
+			supersample: Supersample::new(),
+			render_mode: RenderMode::Escape,
+			coloring: Coloring::Escape,
+			formula: None,
+		}
+	}
+
+	/**
+	 * max_val is the value for the largest exponent; half of it means 0 (see `is_signed()`)
+	 */
+	pub fn new_lyapunov(sequence: Sequence, element_ar: f64) -> Self {
+		FractalSpecs {
+			fractal_type: FractalType::Lyapunov(sequence),
+
+			max_val: 1000,
+			default_width: DEFAULT_LYAPUNOV_WIDTH,
+			default_center: Vector2f::new(3.0, 3.0), 
+			element_ar: element_ar,
+			num_threads: num_cpus::get() as usize,
+			use_multi_threads: true,
+			use_mariani_silver: false,
+			supersample: Supersample::new(),
+			render_mode: RenderMode::Escape,
+			coloring: Coloring::Escape,
+			formula: None,
+		}
+	}
+
+	/**
+	 * The default center and width are the system's bounds 
+	 * (`PointCloudView` adjusts them to fit its matrix)
+	 */
+	pub fn new_point_cloud(system: PointSystem, element_ar: f64) -> Self {
+		let (center, size) = system.bounds();
+		FractalSpecs {
+			fractal_type: FractalType::PointCloud(system),
+
+			max_val: 1000,
+			default_width: size.x,
+			default_center: center, 
+			element_ar: element_ar,
+			num_threads: num_cpus::get() as usize,
+			use_multi_threads: true,
+			use_mariani_silver: false,
+			supersample: Supersample::new(),
+			render_mode: RenderMode::Escape,
+			coloring: Coloring::Escape,
+			formula: None,
+		}
+	}
+
+	/**
+	 * max_val is the max orbit length 
+	 */
+	pub fn new_buddhabrot(element_ar: f64) -> Self {
+		FractalSpecs {
+			fractal_type: FractalType::Buddhabrot(BuddhaMode::Buddhabrot),
+
+			max_val: 1000,
+			default_width: DEFAULT_BUDDHABROT_WIDTH,
+			default_center: Vector2f::new(0.0, 0.0), 
+			element_ar: element_ar,
+			num_threads: num_cpus::get() as usize,
+			use_multi_threads: true,
+			use_mariani_silver: false,
+			supersample: Supersample::new(),
+			render_mode: RenderMode::Escape,
+			coloring: Coloring::Escape,
+			formula: None,
 		}
 	}
 }
 
 
+/**
+ * Simple value object; the result of calculating a cell.
+ *
+ * `value` is what the fractal matrix holds (eg, the iteration count).
+ * `family` selects the set of characters the cell gets drawn with (see `Asciifier`), 
+ * and goes in a separate 'family matrix'; eg, the index of the root whose basin the cell lies in (+1).
+ * It's 0 for fractal types which don't make use of it.
+ */
+#[derive(Clone, Copy)]
+pub struct CellValue {
+	pub value: u16,
+	pub family: u8,
+}
+
+impl CellValue {
+	pub fn new(value: u16) -> Self {
+		CellValue { value: value, family: 0 }
+	}
+}
+
+// supersampling: the max number of families tracked when finding the most common one
+const MAX_FAMILIES: usize = 16;
+
+
+/**
+ * Simple value object; the number of cells written by a call to `FractalCalc::write_matrix`,
+ * split into those which were iterated and those which were filled in without iterating
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct CalcStats {
+	pub iterated: usize,
+	pub filled: usize,
+}
+
+impl CalcStats {
+	pub fn new() -> Self {
+		CalcStats { iterated: 0, filled: 0 }
+	}
+	
+	pub fn add(&mut self, other: &CalcStats) {
+		self.iterated += other.iterated;
+		self.filled += other.filled;
+	}
+}
+
+
+// Mariani-Silver: rectangles smaller than this (in cells, in either dimension) just get iterated 
+const MARIANI_SILVER_MIN_TILE: usize = 4;
+
+
 /**
  * 'Static' class
  * Fills in a `Matrix` with calculated fractal values
@@ -80,12 +391,45 @@ impl FractalCalc {
 		ht		
 	}
 
-	pub fn write_matrix(specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64, matrix: &mut Matrix<u16>) {
+	/**
+	 * step
+	 *      only every nth column of every nth row gets calculated (1 to calculate everything)
+	 * prev_step
+	 *      cells lying on this (coarser) grid are assumed to be calculated already, and are skipped
+	 *      (0 to skip nothing)
+	 * families
+	 *      receives the `CellValue::family` of each cell; must be the same size as `matrix`
+	 */
+	pub fn write_matrix(specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64, 
+			matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, step: usize, prev_step: usize) -> CalcStats {
+		let h = matrix.height();
+		FractalCalc::write_matrix_rows(specs, center, width, rotation, matrix, families, 0, h, step, prev_step)
+	}
+
+	/**
+	 * Like `write_matrix()`, but only calculates the rows from `start_y` to `start_y + num_rows`
+	 * (which lets a costly pass be split up over several frames)
+	 */
+	pub fn write_matrix_rows(specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64, 
+			matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, start_y: usize, num_rows: usize, 
+			step: usize, prev_step: usize) -> CalcStats {
+		let h = matrix.height();
+		// (nothing to do, and the multithreaded version would wait on zero threads)
+		if num_rows == 0 || matrix.width() == 0 {
+			return CalcStats::new();
+		}
 		if specs.use_multi_threads {
-			FractalCalc::write_matrix_mt(&specs, center, width, rotation, matrix);
+			FractalCalc::write_matrix_mt(&specs, center, width, rotation, matrix, families, start_y, num_rows, step, prev_step)
+		} else if start_y == 0 && num_rows == h {
+			FractalCalc::write_matrix_section(&specs, center, width, rotation, matrix, families, 0, h, step, prev_step)
 		} else {
-			let h = matrix.height();
-			FractalCalc::write_matrix_section(&specs, center, width, rotation, matrix, 0, h);
+			let mut section = matrix.section(start_y, num_rows);
+			let mut family_section = families.section(start_y, num_rows);
+			let stats = FractalCalc::write_matrix_section(&specs, center, width, rotation, 
+					&mut section, &mut family_section, start_y, h, step, prev_step);
+			matrix.copy_from(&section, start_y);
+			families.copy_from(&family_section, start_y);
+			stats
 		}
 	}
 
@@ -100,14 +444,19 @@ impl FractalCalc {
 	 *      the center in 'mandelbrot space'
 	 * section
 	 *  	the matrix to be written to (which is a section of the full matrix)
+	 * family_section
+	 *  	the same section of the family matrix
 	 * full_matrix_offset
 	 *      the row from the full matrix where the section starts at
 	 * full_matrix_height
 	 *      height of the full matrix
+	 * step, prev_step
+	 *      see `write_matrix()`; cells which are not calculated are left untouched
 	 */
 	pub fn write_matrix_section(specs: &FractalSpecs, 
 			center: Vector2f, width: f64, rotation: f64, 
-			section: &mut Matrix<u16>,  full_matrix_offset: usize, full_matrix_height: usize) {
+			section: &mut Matrix<u16>, family_section: &mut Matrix<u8>, full_matrix_offset: usize, full_matrix_height: usize,
+			step: usize, prev_step: usize) -> CalcStats {
 		
 		let mandelbrot_height = FractalCalc::get_height(specs, section.width(), full_matrix_height, width);
 	
@@ -120,8 +469,24 @@ impl FractalCalc {
 		let half_matrix_w = section.width() as f64 / 2.0;
 		let half_matrix_h = full_matrix_height as f64 / 2.0;
 
+		if specs.use_mariani_silver {
+			// the point of cell (0,0) of the full matrix 
+			let origin = center + slope_x * -half_matrix_w + slope_y * -half_matrix_h;
+			let mut tiler = MarianiSilver::new(specs, origin, slope_x, slope_y, section, family_section, full_matrix_offset, step, prev_step);
+			tiler.run();
+			return tiler.stats;
+		}
+
+		let mut stats = CalcStats::new();
+
 		for index_y in 0..section.height() {
 
+			let full_y = full_matrix_offset + index_y;
+			if full_y % step != 0 {
+				continue;
+			}
+			let on_prev_row = prev_step > 0 && full_y % prev_step == 0;
+
 			let mut cursor = center;
 			
 			// move to left edge:
@@ -134,40 +499,51 @@ impl FractalCalc {
 		 	
 		 	for index_x in 0..section.width() {
 
-				let value = FractalCalc::get_value(&specs, cursor.x, cursor.y);
-	            section.set(index_x, index_y, value); 
+				let is_calculated = on_prev_row && index_x % prev_step == 0; 
+				if index_x % step == 0 && ! is_calculated {
+					let cell = FractalCalc::get_cell_value(&specs, cursor, slope_x, slope_y, index_x, full_y);
+		            section.set(index_x, index_y, cell.value);
+		            family_section.set(index_x, index_y, cell.family);
+		            stats.iterated += 1;
+				} 
 				
 				// move 'right'
 				cursor.x += slope_x.x;
 				cursor.y += slope_x.y;
 		 	}
 		}
+		
+		stats
 	}
 
 	/**
 	 * Calculate the MandelUtil data in chunks handed off to separate threads
 	 * Then, write the results to the passed-in matrix
 	 */
-	fn write_matrix_mt(specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64, matrix: &mut Matrix<u16>) {
+	fn write_matrix_mt(specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64, 
+			matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, start_y: usize, num_rows: usize, 
+			step: usize, prev_step: usize) -> CalcStats {
 
-		// horizontal strips which make up the final fractal data; 
+		// (every strip needs at least one row)
+		let num_threads = cmp::min(specs.num_threads, num_rows);
+
+		// horizontal strips which make up the final fractal data (and family data); 
 		// the threads' 'work product' goes in here
-		let i = specs.num_threads;
-		let sections: Vec<Matrix<u16>> = vec![Matrix::new(1,1); i]; 
+		let sections: Vec<(Matrix<u16>, Matrix<u8>)> = vec![(Matrix::new(1,1), Matrix::new(1,1)); num_threads]; 
 
 	    // make the data shareable and mutable
 	    let wrapped_data = Arc::new(Mutex::new(sections));
 
-		let (sender, receiver) = mpsc::channel::<bool>();
+		let (sender, receiver) = mpsc::channel::<CalcStats>();
 
-		let step = (matrix.height() as f64 / specs.num_threads as f64).floor() as usize;
-		for i in 0..specs.num_threads {
+		let strip_ht = (num_rows as f64 / num_threads as f64).floor() as usize;
+		for i in 0..num_threads {
 			
-			let start = i as usize * step;
-			let end = if i < specs.num_threads -1 {
-				(i + 1) as usize * step
+			let start = start_y + i as usize * strip_ht;
+			let end = if i < num_threads -1 {
+				start_y + (i + 1) as usize * strip_ht
 			} else {
-				matrix.height()
+				start_y + num_rows
 			};
 			let section_ht = end - start;
 			// println!("i {} start {} end {} ht {}", i, start, end, section_ht);
@@ -179,48 +555,314 @@ impl FractalCalc {
 			
 			// note how we clone self b/c of use of instance method 
 			let spec = specs.clone();	
-			let matrix_w = matrix.width();
 			let matrix_h = matrix.height();
 
+			// start with the existing values, since cells which are skipped must be left intact
+			let mut section: Matrix<u16> = matrix.section(start, section_ht);
+			let mut family_section: Matrix<u8> = families.section(start, section_ht);
 			
 	        thread::spawn(move || {
 
-				let mut section: Matrix<u16> = Matrix::new(matrix_w, section_ht);			
-				FractalCalc::write_matrix_section(&spec, center, width, rotation, &mut section, start, matrix_h);
+				let stats = FractalCalc::write_matrix_section(&spec, center, width, rotation, 
+						&mut section, &mut family_section, start, matrix_h, step, prev_step);
 
                 let mut locked_data = wrapped_data.lock().unwrap();
-				locked_data[i] = section;
+				locked_data[i] = (section, family_section);
 
-                let _ = sender.send(true);
+                let _ = sender.send(stats);
 	        });
 		}
 		
+		let mut stats = CalcStats::new();
 		let mut count = 0;
 		loop {
 	        // this blocks until the channel receiver gets a message
-	        let _ = receiver.recv();
+	        match receiver.recv() {
+	        	Ok(section_stats) => stats.add(&section_stats),
+	        	Err(_) => {}
+	        }
 	        count += 1;
-	        if count == specs.num_threads {
+	        if count == num_threads {
 	        	break;
 	        }
 		}
 
 		// copy the chunks into the passed-in matrix		
         let locked_data = wrapped_data.lock().unwrap();
-		let mut yoff: usize = 0;		
+		let mut yoff: usize = start_y;
 		for i in 0..locked_data.len() {   
-			let section = &locked_data[i];
+			let (ref section, ref family_section) = locked_data[i];
 			matrix.copy_from(&section, yoff);
+			families.copy_from(&family_section, yoff);
 			yoff += section.height();
 		}
+		
+		stats
 	}
 
-	pub fn get_value(specs: &FractalSpecs, x: f64, y: f64) -> u16 {
-		// ersatz-dynamic dispatch (tried other refactoring routes which didn't work out :( )
+	/**
+	 * Returns the value of the cell whose sample point is `p`, 
+	 * taking multiple samples within the cell if supersampling is on
+	 *
+	 * slope_x, slope_y
+	 *      the extent of one cell in fractal space (see `write_matrix_section()`) 
+	 * cell_x, cell_y
+	 *      the cell's location in the full matrix; only used to seed the jitter 
+	 */
+	pub fn get_cell_value(specs: &FractalSpecs, p: Vector2f, slope_x: Vector2f, slope_y: Vector2f, 
+			cell_x: usize, cell_y: usize) -> CellValue {
+
+		let cell_size = slope_x.len();
+		let ss = specs.supersample;
+		if ! ss.is_on() {
+			return FractalCalc::get_value(specs, p.x, p.y, cell_size);
+		}
+
+		let n = ss.size;
+		let mut sum = 0u32;
+		let mut max = 0u16;
+		let mut min = u16::max_value();
+		let mut family_counts = [0u8; MAX_FAMILIES];
+		for j in 0..n {
+			for i in 0..n {
+				// sub-sample offset within the cell, in range (-0.5, +0.5)
+				let (jx, jy) = match ss.pattern {
+					SamplePattern::Grid => (0.5, 0.5),
+					SamplePattern::Jitter => {
+						let h = FractalCalc::hash(cell_x, cell_y, j * n + i);
+						((h & 0xffff) as f64 / 65536.0, (h >> 16) as f64 / 65536.0)
+					}
+				};
+				let ox = (i as f64 + jx) / n as f64 - 0.5;
+				let oy = (j as f64 + jy) / n as f64 - 0.5;
+				let q = p + slope_x * ox + slope_y * oy;
+				let cell = FractalCalc::get_value(specs, q.x, q.y, cell_size);
+				let value = cell.value;
+				family_counts[cell.family as usize % MAX_FAMILIES] += 1;
+				sum += value as u32;
+				if value > max { max = value; }
+				if value < min { min = value; }
+			}
+		}
+		let value = match ss.reduce {
+			SampleReduce::Average => ((sum as f64 / (n * n) as f64) + 0.5) as u16,
+			SampleReduce::Max => max,
+			SampleReduce::Min => min,
+		};
+		
+		// most common family
+		let mut family = 0;
+		for i in 0..MAX_FAMILIES {
+			if family_counts[i] > family_counts[family] {
+				family = i;
+			}
+		}
+		
+		CellValue { value: value, family: family as u8 }
+	}
+
+	/**
+	 * Cheap integer hash, used for jitter that stays the same between frames
+	 */
+	pub fn hash(x: usize, y: usize, i: usize) -> u32 {
+		let mut h = (x as u32).wrapping_mul(0x8da6b343) ^ (y as u32).wrapping_mul(0xd8163841) ^ (i as u32).wrapping_mul(0xcb1ab31f);
+		h ^= h >> 15;
+		h = h.wrapping_mul(0x2c1b3c6d);
+		h ^= h >> 12;
+		h
+	}
+
+	/**
+	 * cell_size - the width of a cell in fractal space; used to scale distance estimates
+	 */
+	pub fn get_value(specs: &FractalSpecs, x: f64, y: f64, cell_size: f64) -> CellValue {
 		match specs.fractal_type {
-			FractalType::Mandelbrot => FractalCalc::get_mandelbrot_value(x, y, specs.max_val),
+			FractalType::Newton(ref poly) => return FractalCalc::get_newton_value(poly, x, y, specs.max_val),
+			FractalType::Lyapunov(ref sequence) => return FractalCalc::get_lyapunov_value(sequence, x, y, specs.max_val),
+			_ => {}
+		}
+		match specs.formula {
+			Some(ref formula) => {
+				let (z, c) = match specs.fractal_type {
+					FractalType::Julia(c) => (Complex { re: x, im: y }, c),
+					_ => (Complex { re: x, im: y }, Complex { re: x, im: y }),
+				};
+				return CellValue::new(FractalCalc::get_formula_value(formula, z, c, specs.max_val));
+			},
+			None => {}
+		}
+		if specs.uses_distance() {
+			return CellValue::new(FractalCalc::get_distance_value(specs, x, y, cell_size));
+		}
+		if specs.coloring != Coloring::Escape {
+			let (z, c) = match specs.fractal_type {
+				FractalType::Julia(c) => (Complex { re: x, im: y }, c),
+				_ => (Complex { re: 0.0, im: 0.0 }, Complex { re: x, im: y }),
+			};
+			let ratio = specs.coloring.get_ratio(z, c, specs.max_val);
+			return CellValue::new((ratio * specs.max_val as f64 + 0.5) as u16);
+		}
+		// ersatz-dynamic dispatch (tried other refactoring routes which didn't work out :( )
+		let value = match specs.fractal_type {
 			FractalType::Julia(c) => FractalCalc::get_julia_value(&c, x, y, specs.max_val),
+			_ => FractalCalc::get_mandelbrot_value(x, y, specs.max_val),
+		};
+		CellValue::new(value)
+	}
+
+	/**
+	 * Escape time, using a custom formula.
+	 * Note that mandelbrot-style iteration starts at z = c rather than 0 (which is a fixed point of eg 'sin(z)*c')
+	 */
+	fn get_formula_value(formula: &Formula, mut z: Complex64, c: Complex64, max_val: u16) -> u16 {
+		for val in 0..max_val {
+			if z.norm_sqr() > FORMULA_BAILOUT * FORMULA_BAILOUT || z.re.is_nan() || z.im.is_nan() {
+				return val;
+			}
+			z = formula.eval(z, c);
+		}
+		max_val
+	}
+
+	/**
+	 * Lyapunov exponent of the logistic map x = r * x * (1 - x), 
+	 * where r alternates between a and b according to the sequence. 
+	 * 
+	 * The value is the exponent, scaled and offset so that half of max_val is 0;
+	 * the family is 1 for negative exponents (stable) and 2 for positive (chaotic)
+	 */
+	fn get_lyapunov_value(sequence: &Sequence, a: f64, b: f64, max_val: u16) -> CellValue {
+		
+		if a < 0.0 || a > 4.0 || b < 0.0 || b > 4.0 {
+			// the logistic map is unbounded outside of this range
+			return CellValue { value: 0, family: 0 };
+		}
+		
+		let mut x = 0.5;
+		for i in 0..LYAPUNOV_WARMUP {
+			let r = sequence.rate(i, a, b);
+			x = r * x * (1.0 - x);
+		}
+		let mut sum = 0.0;
+		for i in LYAPUNOV_WARMUP..(LYAPUNOV_WARMUP + LYAPUNOV_ITERATIONS) {
+			let r = sequence.rate(i, a, b);
+			x = r * x * (1.0 - x);
+			let d = (r * (1.0 - 2.0 * x)).abs();
+			if d == 0.0 {
+				// superstable
+				sum = ::std::f64::NEG_INFINITY;
+				break;
+			}
+			sum += d.ln();
+		}
+		let exponent = sum / LYAPUNOV_ITERATIONS as f64;
+		
+		let half = max_val as f64 / 2.0;
+		let value = (half + exponent * LYAPUNOV_SCALE).max(0.0).min(max_val as f64);
+		let family = if exponent < 0.0 { 1 } else { 2 };
+		CellValue { value: (value + 0.5) as u16, family: family }
+	}
+
+	/**
+	 * Newton's method; the value is the number of iterations it took to converge, 
+	 * and the family is the index of the root it converged to, plus 1 (0 if it didn't converge)  
+	 */
+	fn get_newton_value(poly: &Polynomial, x: f64, y: f64, max_val: u16) -> CellValue {
+		let mut z = Complex { re: x, im: y };
+		for val in 0..max_val {
+			let roots = poly.roots();
+			for i in 0..roots.len() {
+				if (z - roots[i]).norm_sqr() < NEWTON_EPSILON * NEWTON_EPSILON {
+					return CellValue { value: val, family: (i + 1) as u8 };
+				}
+			}
+			let (p, dp) = poly.eval_with_derivative(z);
+			if dp.norm_sqr() == 0.0 {
+				break;
+			}
+			z = z - p / dp;
+		}
+		CellValue { value: max_val, family: 0 }
+	}
+
+	/**
+	 * Maps the distance estimate (in cells) to a value from 0 (far from the set) to max_val (on the boundary, or inside).
+	 * With `RenderMode::DistanceBand`, the escape-time iteration band takes up the lower part of the range. 
+	 */
+	fn get_distance_value(specs: &FractalSpecs, x: f64, y: f64, cell_size: f64) -> u16 {
+
+		let (val, distance) = match specs.fractal_type {
+			FractalType::Julia(c) => FractalCalc::get_julia_distance(&c, x, y, specs.max_val),
+			_ => FractalCalc::get_mandelbrot_distance(x, y, specs.max_val),
+		};
+		let distance = match distance {
+			Some(d) => d,
+			None => return specs.max_val,  // inside
+		};
+
+		let cells = distance / cell_size;
+		let mut closeness = 1.0 - ((1.0 + cells).log2() / DE_RANGE_LOG2);
+		if closeness < 0.0 {
+			closeness = 0.0;
 		}
+		
+		let ratio = match specs.render_mode {
+			RenderMode::DistanceBand => {
+				let band = (1.0 + val as f64).ln() / (1.0 + specs.max_val as f64).ln();
+				if closeness > 0.5 {
+					// line takes precedence
+					DE_BAND_WEIGHT + closeness * (1.0 - DE_BAND_WEIGHT)
+				} else {
+					band * DE_BAND_WEIGHT
+				}
+			},
+			_ => closeness
+		};
+		(ratio * specs.max_val as f64 + 0.5) as u16
+	}
+
+	/**
+	 * Returns iteration count and exterior distance estimate (None if the point doesn't escape),
+	 * tracking the derivative dz/dc alongside z
+	 */
+	fn get_mandelbrot_distance(x: f64, y: f64, max_val: u16) -> (u16, Option<f64>) {
+		let c = Complex { re: x, im: y };
+		let mut z = Complex { re: 0f64, im: 0f64 };
+		let mut dz = Complex { re: 0f64, im: 0f64 };
+		let one = Complex { re: 1f64, im: 0f64 };
+		for val in 0..max_val {
+			if z.norm_sqr() > DE_BAILOUT * DE_BAILOUT {
+				return (val, Some(FractalCalc::distance_estimate(z, dz)));
+			}
+			dz = z * dz * 2.0 + one;
+			z = z * z + c;
+		}
+		(max_val, None)
+	}
+
+	/**
+	 * Same as above, but with derivative dz/dz0
+	 */
+	fn get_julia_distance(c: &Complex64, x: f64, y: f64, max_val: u16) -> (u16, Option<f64>) {
+		let mut z = Complex { re: x, im: y };
+		let mut dz = Complex { re: 1f64, im: 0f64 };
+		for val in 0..max_val {
+			if z.norm_sqr() > DE_BAILOUT * DE_BAILOUT {
+				return (val, Some(FractalCalc::distance_estimate(z, dz)));
+			}
+			dz = z * dz * 2.0;
+			z = z * z + c;
+		}
+		(max_val, None)
+	}
+
+	fn distance_estimate(z: Complex64, dz: Complex64) -> f64 {
+		let z_abs = z.norm_sqr().sqrt();
+		let dz_abs = dz.norm_sqr().sqrt();
+		if dz_abs == 0.0 {
+			return 0.0;
+		}
+		0.5 * z_abs * z_abs.ln() / dz_abs
 	}
 
 	fn get_mandelbrot_value(x: f64, y: f64, max_val: u16) -> u16 {
@@ -246,3 +888,186 @@ impl FractalCalc {
         max_val
 	}
 }
+
+
+/**
+ * Mariani-Silver subdivision over a section of the fractal matrix.
+ *
+ * The border cells of a rectangle are calculated; if they all have the same value, the rectangle's interior 
+ * is filled in with that value without being iterated. Otherwise, the rectangle is split into quarters
+ * (which share their borders with each other), and so on.
+ *
+ * Rectangles are in 'grid' coordinates (ie, in units of `step`), so that this works with 
+ * the sparse passes of `Progressive` as well.  
+ *
+ * As a guard against thin filaments (and islands) which lie inside a rectangle without crossing its border,
+ * a few interior 'probe' cells must also match the border before it gets filled, 
+ * as must any interior cells that were calculated by a previous pass.
+ */
+struct MarianiSilver<'a> {
+	specs: &'a FractalSpecs,
+	origin: Vector2f,  // the point of cell (0,0) of the full matrix
+	slope_x: Vector2f,
+	slope_y: Vector2f,
+	section: &'a mut Matrix<u16>,
+	family_section: &'a mut Matrix<u8>,
+	full_matrix_offset: usize,
+	first_y: usize,  // first row of the section which lies on the grid
+	step: usize,
+	done: Option<Matrix<bool>>,  // grid cells whose values are known; None if the section has no grid rows
+	stats: CalcStats,
+}
+
+impl<'a> MarianiSilver<'a> {
+
+	fn new(specs: &'a FractalSpecs, origin: Vector2f, slope_x: Vector2f, slope_y: Vector2f, 
+			section: &'a mut Matrix<u16>, family_section: &'a mut Matrix<u8>, 
+			full_matrix_offset: usize, step: usize, prev_step: usize) -> Self {
+		
+		let first_y = (step - full_matrix_offset % step) % step;
+		
+		let done = if first_y >= section.height() {
+			None
+		} else {
+			let grid_w = (section.width() - 1) / step + 1;
+			let grid_h = (section.height() - 1 - first_y) / step + 1;
+			let mut done = Matrix::new(grid_w, grid_h);
+			if prev_step > 0 {
+				for gy in 0..grid_h {
+					for gx in 0..grid_w {
+						let x = gx * step;
+						let full_y = full_matrix_offset + first_y + gy * step;
+						done.set(gx, gy, x % prev_step == 0 && full_y % prev_step == 0);
+					}
+				}
+			}
+			Some(done)
+		};
+
+		MarianiSilver {
+			specs: specs, origin: origin, slope_x: slope_x, slope_y: slope_y,
+			section: section, family_section: family_section, full_matrix_offset: full_matrix_offset, first_y: first_y, step: step,
+			done: done, stats: CalcStats::new(),
+		}
+	}
+
+	fn run(&mut self) {
+		let (w, h) = match self.done {
+			Some(ref done) => (done.width(), done.height()),
+			None => return,
+		};
+		self.subdivide(0, 0, w - 1, h - 1);
+	}
+
+	fn is_done(&self, gx: usize, gy: usize) -> bool {
+		match self.done {
+			Some(ref done) => done.get(gx, gy),
+			None => true,
+		}
+	}
+
+	fn set_done(&mut self, gx: usize, gy: usize) {
+		match self.done {
+			Some(ref mut done) => done.set(gx, gy, true),
+			None => {},
+		}
+	}
+
+	/**
+	 * Returns the value and family of the grid cell, iterating it if it's not yet known
+	 */
+	fn value(&mut self, gx: usize, gy: usize) -> (u16, u8) {
+		let x = gx * self.step;
+		let y = self.first_y + gy * self.step;
+		if self.is_done(gx, gy) {
+			return (self.section.get(x, y), self.family_section.get(x, y));
+		}
+		let full_y = self.full_matrix_offset + y;
+		let p = self.origin + self.slope_x * x as f64 + self.slope_y * full_y as f64;
+		let cell = FractalCalc::get_cell_value(self.specs, p, self.slope_x, self.slope_y, x, full_y);
+		self.section.set(x, y, cell.value);
+		self.family_section.set(x, y, cell.family);
+		self.set_done(gx, gy);
+		self.stats.iterated += 1;
+		(cell.value, cell.family)
+	}
+
+	// rem, bounds are inclusive
+	fn subdivide(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
+
+		// border
+		let v = self.value(x0, y0);
+		let mut is_uniform = true;
+		for x in x0..(x1 + 1) {
+			if self.value(x, y0) != v { is_uniform = false; }
+			if self.value(x, y1) != v { is_uniform = false; }
+		}
+		for y in (y0 + 1)..y1 {
+			if self.value(x0, y) != v { is_uniform = false; }
+			if self.value(x1, y) != v { is_uniform = false; }
+		}
+
+		let w = x1 - x0;
+		let h = y1 - y0;
+		if w < 2 || h < 2 {
+			// no interior
+			return;
+		}
+
+		if w < MARIANI_SILVER_MIN_TILE || h < MARIANI_SILVER_MIN_TILE {
+			for y in (y0 + 1)..y1 {
+				for x in (x0 + 1)..x1 {
+					self.value(x, y);
+				}
+			}
+			return;
+		}
+		
+		if is_uniform {
+			// filament guard: center, and midway between center and corners
+			let probes = [ (x0 + w / 2, y0 + h / 2), 
+					(x0 + w / 4, y0 + h / 4), (x1 - w / 4, y0 + h / 4), 
+					(x0 + w / 4, y1 - h / 4), (x1 - w / 4, y1 - h / 4) ];
+			for &(x, y) in probes.iter() {
+				if self.value(x, y) != v { 
+					is_uniform = false;
+					break; 
+				}
+			}
+		}
+		
+		if is_uniform {
+			// ... and cells from previous passes
+			'outer: for y in (y0 + 1)..y1 {
+				for x in (x0 + 1)..x1 {
+					if self.is_done(x, y) && self.value(x, y) != v {
+						is_uniform = false;
+						break 'outer;
+					}
+				}
+			}
+		}
+
+		if is_uniform {
+			for y in (y0 + 1)..y1 {
+				for x in (x0 + 1)..x1 {
+					if ! self.is_done(x, y) {
+						let sy = self.first_y + y * self.step;
+						self.section.set(x * self.step, sy, v.0);
+						self.family_section.set(x * self.step, sy, v.1);
+						self.set_done(x, y);
+						self.stats.filled += 1;
+					}
+				}
+			}
+			return;
+		}
+		
+		let mx = x0 + w / 2;
+		let my = y0 + h / 2;
+		self.subdivide(x0, y0, mx, my);
+		self.subdivide(mx, y0, x1, my);
+		self.subdivide(x0, my, mx, y1);
+		self.subdivide(mx, my, x1, y1);
+	}
+}
@@ -0,0 +1,269 @@
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use leelib::vector2::Vector2f;
+use fract::tonecurve::ToneCurve;
+
+
+// the most states kept per view (the oldest get dropped)
+const MAX_LENGTH: usize = 100;
+
+// how long the camera has to stay still before its state gets recorded
+pub const PAUSE_FRAMES: u32 = 45;
+
+// the camera counts as still while it moves less than this per frame (as a ratio of the view's width)
+pub const STILL_RATIO: f64 = 0.0005;
+
+// states closer than this to the current one (as a ratio of the width) don't get recorded
+const MIN_CHANGE_RATIO: f64 = 0.05;
+
+// kept next to the config file
+const HISTORY_FILENAME: &'static str = ".fractal-term-rs-history";
+
+
+/**
+ * Where the camera is
+ */
+#[derive(Clone, Copy)]
+pub struct CameraState {
+	pub center: Vector2f,
+	pub width: f64,
+	pub rotation: f64,
+}
+
+impl CameraState {
+
+	/**
+	 * ratio - how close counts as near, relative to the larger of the two widths
+	 */
+	pub fn is_near(&self, other: &CameraState, ratio: f64) -> bool {
+		let w = self.width.max(other.width);
+		let dx = self.center.x - other.center.x;
+		let dy = self.center.y - other.center.y;
+		(dx * dx + dy * dy).sqrt() < w * ratio
+				&& (self.width - other.width).abs() < w * ratio
+				&& (self.rotation - other.rotation).abs() < ratio
+	}
+}
+
+
+/**
+ * A view's back/forward stack of camera states, like a browser's
+ */
+pub struct History {
+	states: Vec<CameraState>,
+	index: usize,  // the current state (when not empty)
+	pub tone_curve: Option<ToneCurve>,  // the view's tone curve, once one has been chosen
+}
+
+impl History {
+
+	pub fn new() -> History {
+		History { states: Vec::new(), index: 0, tone_curve: None }
+	}
+
+	/**
+	 * Adds the state after the current one, dropping any states ahead of it.
+	 * Returns false if the state is too close to the current one to be worth recording.
+	 */
+	pub fn record(&mut self, state: CameraState) -> bool {
+		match self.current() {
+			Some(current) if current.is_near(&state, MIN_CHANGE_RATIO) => return false,
+			_ => {}
+		}
+		if self.states.len() > 0 {
+			self.states.truncate(self.index + 1);
+		}
+		self.states.push(state);
+		if self.states.len() > MAX_LENGTH {
+			self.states.remove(0);
+		}
+		self.index = self.states.len() - 1;
+		true
+	}
+
+	pub fn back(&mut self) -> Option<CameraState> {
+		if self.index == 0 || self.states.len() == 0 {
+			return None;
+		}
+		self.index -= 1;
+		Some(self.states[self.index])
+	}
+
+	pub fn forward(&mut self) -> Option<CameraState> {
+		if self.index + 1 >= self.states.len() {
+			return None;
+		}
+		self.index += 1;
+		Some(self.states[self.index])
+	}
+
+	pub fn current(&self) -> Option<CameraState> {
+		if self.states.len() == 0 { None } else { Some(self.states[self.index]) }
+	}
+
+	/**
+	 * Returns the current state's number (starting from 1) and the number of states
+	 */
+	pub fn position(&self) -> (usize, usize) {
+		if self.states.len() == 0 { (0, 0) } else { (self.index + 1, self.states.len()) }
+	}
+}
+
+
+/**
+ * 'Static' class
+ * Persists the views' histories between sessions, one state per line, as in:
+ *   mandelbrot -0.75 0.1 0.5 0 *
+ * (view type, center x and y, width, rotation, and '*' for the view's current state),
+ * plus a line for each view whose tone curve has been chosen, as in:
+ *   tone mandelbrot gamma:2.2
+ */
+pub struct HistoryFile;
+
+impl HistoryFile {
+
+	/**
+	 * ids - the views' type ids (see `FractalType::id()`), in order
+	 * Returns a history for each; bad lines get skipped.
+	 */
+	pub fn load(ids: &Vec<&'static str>) -> Vec<History> {
+
+		let mut histories: Vec<History> = ids.iter().map(|_| History::new()).collect();
+		let text = match HistoryFile::read_file() {
+			Some(s) => s,
+			None => return histories
+		};
+
+		for line in text.lines() {
+			let v: Vec<&str> = line.split_whitespace().collect();
+			if v.len() == 3 && v[0] == "tone" {
+				match (ids.iter().position(|id| *id == v[1]), ToneCurve::parse(v[2])) {
+					(Some(i), Ok(curve)) => histories[i].tone_curve = Some(curve),
+					_ => {}
+				}
+				continue;
+			}
+			if v.len() < 5 || v[0].starts_with('#') {
+				continue;
+			}
+			let i = match ids.iter().position(|id| *id == v[0]) {
+				Some(i) => i,
+				None => continue
+			};
+			let nums: Vec<f64> = v[1..5].iter().filter_map(|s| s.parse::<f64>().ok()).collect();
+			if nums.len() < 4 || ! nums.iter().all(|n| n.is_finite()) || nums[2] <= 0.0 {
+				continue;
+			}
+			let history = &mut histories[i];
+			history.states.push(CameraState { center: Vector2f::new(nums[0], nums[1]), width: nums[2], rotation: nums[3] });
+			if v.len() > 5 && v[5] == "*" {
+				history.index = history.states.len() - 1;
+			}
+		}
+
+		for history in histories.iter_mut() {
+			let n = history.states.len();
+			if n > MAX_LENGTH {
+				history.states.drain(0..(n - MAX_LENGTH));
+				history.index = history.index.saturating_sub(n - MAX_LENGTH);
+			}
+			if history.index >= history.states.len() {
+				history.index = history.states.len().saturating_sub(1);
+			}
+		}
+		histories
+	}
+
+	pub fn save(ids: &Vec<&'static str>, histories: &Vec<History>) -> Result<(), String> {
+
+		let mut text = "# view, center x and y, width, rotation, and '*' for the current state (or 'tone', view, tone curve)\n".to_string();
+		for (i, history) in histories.iter().enumerate() {
+			for (j, state) in history.states.iter().enumerate() {
+				let mark = if j == history.index { " *" } else { "" };
+				text = text + &format!("{} {} {} {} {}{}\n",
+						ids[i], state.center.x, state.center.y, state.width, state.rotation, mark);
+			}
+			match history.tone_curve {
+				Some(ref curve) => text = text + &format!("tone {} {}\n", ids[i], curve),
+				None => {}
+			}
+		}
+
+		let path = match HistoryFile::path() {
+			Some(p) => p,
+			None => return Err("No home directory".to_string())
+		};
+		match File::create(&path) {
+			Ok(mut file) => match file.write_all(text.as_bytes()) {
+				Ok(_) => Ok(()),
+				Err(e) => Err(format!("{}", e))
+			},
+			Err(e) => Err(format!("{}", e))
+		}
+	}
+
+	fn path() -> Option<String> {
+		match env::var("HOME") {
+			Ok(home) => Some(format!("{}/{}", home, HISTORY_FILENAME)),
+			Err(_) => None
+		}
+	}
+
+	fn read_file() -> Option<String> {
+		let path = match HistoryFile::path() {
+			Some(p) => p,
+			None => return None
+		};
+		let mut text = String::new();
+		match File::open(&path) {
+			Ok(mut file) => match file.read_to_string(&mut text) {
+				Ok(_) => Some(text),
+				Err(_) => None
+			},
+			Err(_) => None
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn state(x: f64) -> CameraState {
+		CameraState { center: Vector2f::new(x, 0.0), width: 1.0, rotation: 0.0 }
+	}
+
+	#[test]
+	fn back_back_forward() {
+		let mut history = History::new();
+		for &x in [0.0, 1.0, 2.0].iter() {
+			assert!(history.record(state(x)));
+		}
+		assert_eq!(history.position(), (3, 3));
+
+		// (re-recording the current state, as going back does, changes nothing)
+		assert!(! history.record(state(2.0)));
+		assert_eq!(history.back().unwrap().center.x, 1.0);
+		assert_eq!(history.back().unwrap().center.x, 0.0);
+		assert!(history.back().is_none());
+		assert_eq!(history.forward().unwrap().center.x, 1.0);
+		assert_eq!(history.forward().unwrap().center.x, 2.0);
+		assert!(history.forward().is_none());
+		assert_eq!(history.position(), (3, 3));
+	}
+
+	#[test]
+	fn record_drops_states_ahead() {
+		let mut history = History::new();
+		for &x in [0.0, 1.0, 2.0].iter() {
+			history.record(state(x));
+		}
+		history.back();
+		history.back();
+		assert!(history.record(state(5.0)));
+		assert_eq!(history.position(), (2, 2));
+		assert!(history.forward().is_none());
+	}
+}
@@ -0,0 +1,101 @@
+extern crate num;
+
+use self::num::complex::Complex64;
+use leelib::vector2::Vector2f;
+use fract::constants;
+
+
+/**
+ * Which edge of the screen the HUD gets drawn along
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum HudPosition {
+	Top,
+	Bottom,
+}
+
+impl HudPosition {
+
+	pub fn parse(s: &str) -> Option<HudPosition> {
+		match &s.trim().to_lowercase()[..] {
+			"top" => Some(HudPosition::Top),
+			"bottom" => Some(HudPosition::Bottom),
+			_ => None
+		}
+	}
+}
+
+
+/**
+ * The values shown by the HUD, as gathered from the current view
+ */
+pub struct HudInfo {
+	pub center: Vector2f,
+	pub cell_size: f64,  // the width of a cell in fractal space; determines how many digits the center gets
+	pub zoom: f64,
+	pub rotation: f64,
+	pub max_val: u16,
+	pub julia_c: Option<Complex64>,
+	pub exposure: (f64, f64, f64),  // floor, ceil, bias
+	pub is_exposure_locked: bool,
+	pub is_equalizing: bool,  // (bias doesn't apply)
+	pub fps: f64,
+}
+
+
+/**
+ * 'Static' class
+ * Formats the status-line HUD
+ */
+pub struct Hud;
+
+impl Hud {
+
+	/**
+	 * Returns the HUD's text, with its fields packed into as many lines as it takes to fit in `max_width`
+	 */
+	pub fn lines(info: &HudInfo, max_width: usize) -> Vec<String> {
+
+		// enough decimal places to tell adjacent cells apart
+		let digits = (-info.cell_size.log10()).ceil().max(0.0) as usize + 1;
+		let digits = digits.min(17);
+		let degrees = (info.rotation / constants::DEG) % 360.0;
+		let degrees = if degrees < 0.0 { degrees + 360.0 } else { degrees };
+
+		let mut fields = vec![
+			format!("x {:+.*}", digits, info.center.x),
+			format!("y {:+.*}", digits, info.center.y),
+			format!("zoom {:.1}x", info.zoom),
+			format!("rot {:.0}°", degrees),
+			format!("iter {}", info.max_val),
+		];
+		match info.julia_c {
+			Some(c) => fields.push(format!("c {:+.6} {:+.6}i", c.re, c.im)),
+			None => {}
+		}
+		if info.is_exposure_locked {
+			fields.push(format!("exp {:.0}-{:.0} lock", info.exposure.0, info.exposure.1));
+		} else if info.is_equalizing {
+			fields.push(format!("exp {:.0}-{:.0} eq", info.exposure.0, info.exposure.1));
+		} else {
+			fields.push(format!("exp {:.0}-{:.0} {:+.2}", info.exposure.0, info.exposure.1, info.exposure.2));
+		}
+		fields.push(format!("{:.1} fps", info.fps));
+
+		let separator = " | ";
+		let mut lines = Vec::new();
+		let mut line = String::new();
+		for field in fields {
+			if line.len() > 0 && line.chars().count() + separator.len() + field.chars().count() + 2 > max_width {
+				lines.push(format!(" {} ", line));
+				line = String::new();
+			}
+			if line.len() > 0 {
+				line.push_str(separator);
+			}
+			line.push_str(&field);
+		}
+		lines.push(format!(" {} ", line));
+		lines
+	}
+}
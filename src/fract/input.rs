@@ -1,5 +1,6 @@
 use std::thread;
 use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
 use rustbox;
 use rustbox::{RustBox, Key, Mouse, EventResult};
 use rustbox::Event::{KeyEvent, MouseEvent, ResizeEvent};
@@ -9,13 +10,15 @@ use rustbox::Event::{KeyEvent, MouseEvent, ResizeEvent};
  * Spawns a thread which loops, polling for keyboard and mouse input using rustbox.
  * (Rustbox is only used for this purpose, not for any terminal output).
  * 
- * Note how data is not passed using a channel's sender, but by mutating the passed-in argument, 
- * which is shared with the main thread. This value acts as a flag.
+ * Note how data is not passed using a channel's sender, but by pushing onto the passed-in queue, 
+ * which is shared with the main thread (which drains it once per frame).
+ *
+ * While a text prompt is open (see `Command::PromptOpen`), keys are passed through as text
+ * rather than being mapped to the usual commands.
  *
- * TODO: Should not be a flag so much as a queue... (eg, fast mousewheel operations lag)
  * TODO: The use of app-specific 'Commands' as an extra abstraction has proven to be not all that useful; should flatten or smth
  */
-pub fn launch_thread(wrapped_command: Arc<Mutex<Command>>) -> thread::JoinHandle<()> {
+pub fn launch_thread(wrapped_commands: Arc<Mutex<VecDeque<Command>>>) -> thread::JoinHandle<()> {
 
     thread::spawn(move || {
 
@@ -26,31 +29,47 @@ pub fn launch_thread(wrapped_command: Arc<Mutex<Command>>) -> thread::JoinHandle
 	    }; 
 
 		{
-			// immediately set the command to tell app the terminal's character dimensions
-			let mut locked_command = wrapped_command.lock().unwrap(); 
-			*locked_command = Command::Size(rustbox.width(), rustbox.height());
+			// immediately send a command to tell app the terminal's character dimensions
+			let mut locked_commands = wrapped_commands.lock().unwrap(); 
+			locked_commands.push_back(Command::Size(rustbox.width(), rustbox.height()));
 		}	    		
 	
+		let mut is_prompting = false;
+	
 	    loop {
  
 			let event = rustbox.poll_event(false);  // rem, this BLOCKS
 	        // TODO: use this instead, and rip out the thread nonsense
 	        // let event = rustbox.peek_event(Duration::from_millis(5000), false); 
 	        
-			let mut locked_command = wrapped_command.lock().unwrap();
-			*locked_command = Command::from_rustbox_event(event);
-
-	        match *locked_command {
-	        	Command::Quit => {  
-	        		break;
-	        	}
-	        	_ => {} 
+			let command = Command::from_rustbox_event(event, &mut is_prompting);
+			let is_quit = match command { 
+				Command::Quit => true, 
+				Command::None => continue,
+				_ => false 
+			};
+			
+			let mut locked_commands = wrapped_commands.lock().unwrap();
+			locked_commands.push_back(command);
+
+	        if is_quit {
+	        	break;
 	        }
 	    }
     })
 }	
 
 
+/**
+ * Which text prompt is open
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+	Formula,
+	Location,
+}
+
+
 #[derive(Debug)]
 pub enum Command {
 	ChangeFractalSet,
@@ -61,23 +80,42 @@ pub enum Command {
     RotationalVelocity(f64),
     Size(usize, usize),
     Coord(usize),
-    AutoExposure, Help, Stop, Reset, Quit, 
+    AutoExposure, ExposureMode, ExposureLock, ExposureShift(f64), ExposureScale(f64), ToneCurve, ToneParam(i32), 
+    RampCycle, RampAuto, Charset(i32), Dither, Edges, Transition, HistoryBack, HistoryForward, Undo, Redo, SeedPath, Orbit, Progressive, Upscale, MarianiSilver, 
+    Supersample, SampleReduce, SamplePattern, Calibrate, RenderMode, Coloring, SplitView, Minimap, Hud,
+    CopyLocation, PromptOpen(Prompt), PromptChar(char), PromptBackspace, PromptEnter, PromptCancel,
+    Help, Stop, Reset, Quit, 
     None, 
     // TODO: use 'Option' pattern instead of 'none' ?  
 }
 
 impl Command {
 
-	pub fn from_rustbox_event(event_result: EventResult) -> Command {
+	/**
+	 * is_prompting - whether a text prompt is open; gets updated as the prompt is opened and closed 
+	 */
+	pub fn from_rustbox_event(event_result: EventResult, is_prompting: &mut bool) -> Command {
 
 		let event = event_result.unwrap();
         match event {
         
             KeyEvent(key) => {				
+            	
+            	if *is_prompting {
+            		return match key {
+            			Key::Enter => { *is_prompting = false; Command::PromptEnter },
+            			Key::Esc => { *is_prompting = false; Command::PromptCancel },
+            			Key::Ctrl('c') => Command::Quit,
+            			Key::Backspace => Command::PromptBackspace,
+            			Key::Char(c) => Command::PromptChar(c),
+            			_ => Command::None,
+            		};
+            	}
                 
                 match key {
                 	
                     Key::Char('f') | Key::Char('F') => Command::ChangeFractalSet,
+                    Key::Char('~') => Command::Transition,
 
                     Key::Left => Command::PositionVelocity(-1.0, 0.0),
                     Key::Right => Command::PositionVelocity(1.0, 0.0),
@@ -106,6 +144,42 @@ impl Command {
                     Key::Char('0') => Command::Coord(9),
                     
                     Key::Char('e') | Key::Char('E') => Command::AutoExposure,
+                    Key::Char('#') => Command::ExposureMode,
+                    Key::Char('l') | Key::Char('L') => Command::ExposureLock,
+                    Key::Char('b') => Command::ExposureShift(-0.1),
+                    Key::Char('B') => Command::ExposureShift(0.1),
+                    Key::Char('n') => Command::ExposureScale(0.8),
+                    Key::Char('N') => Command::ExposureScale(1.25),
+                    Key::Char('k') => Command::RampCycle,
+                    Key::Char('K') => Command::RampAuto,
+                    Key::Char('w') => Command::Charset(1),
+                    Key::Char('W') => Command::Charset(-1),
+                    Key::Char('t') | Key::Char('T') => Command::ToneCurve,
+                    Key::Char(',') | Key::Char('<') => Command::ToneParam(-1),
+                    Key::Char('.') | Key::Char('>') => Command::ToneParam(1),
+                    Key::Char('p') => Command::Progressive,
+                    Key::Char('P') => Command::Upscale,
+                    Key::Char('m') | Key::Char('M') => Command::MarianiSilver,
+                    Key::Char('x') => Command::Supersample,
+                    Key::Char('X') => Command::SampleReduce,
+                    Key::Char('j') | Key::Char('J') => Command::SamplePattern,
+                    Key::Char('C') => Command::Calibrate,
+                    Key::Char('d') => Command::RenderMode,
+                    Key::Char('D') => Command::Dither,
+                    Key::Char('q') | Key::Char('Q') => Command::Edges,
+                    Key::Char('c') => Command::Coloring,
+                    Key::Char('v') | Key::Char('V') => Command::SplitView,
+                    Key::Char('o') | Key::Char('O') => Command::Minimap,
+                    Key::Char('i') | Key::Char('I') => Command::Hud,
+                    Key::Char(':') => { *is_prompting = true; Command::PromptOpen(Prompt::Formula) },
+                    Key::Char('g') | Key::Char('G') => { *is_prompting = true; Command::PromptOpen(Prompt::Location) },
+                    Key::Char('y') | Key::Char('Y') => Command::CopyLocation,
+                    Key::Char('u') => Command::Undo,
+                    Key::Char('U') => Command::Redo,
+                    Key::Char('(') | Key::Backspace => Command::HistoryBack,
+                    Key::Char(')') => Command::HistoryForward,
+                    Key::Char('s') => Command::SeedPath,
+                    Key::Char('S') => Command::Orbit,
                     Key::Char(' ') => Command::Stop,
                     Key::Char('r') | Key::Char('R') => Command::Reset,
                     Key::Esc | Key::Ctrl('c') => Command::Quit,
@@ -132,4 +206,21 @@ impl Command {
    		    }
         }
 	}
+
+	/**
+	 * Whether the command leaves a running Mandelbrot zoom alone
+	 * (ie, it only changes how things look, or doesn't touch the view at all)
+	 */
+	pub fn keeps_coord_anim(&self) -> bool {
+		match *self {
+			Command::RotationalVelocity(_) | 
+					Command::AutoExposure | Command::ExposureMode | Command::ExposureLock | Command::ExposureShift(_) | Command::ExposureScale(_) | 
+					Command::ToneCurve | Command::ToneParam(_) | Command::RampCycle | Command::RampAuto | Command::Charset(_) | 
+					Command::Dither | Command::Edges | Command::Transition | Command::SeedPath | 
+					Command::Progressive | Command::Upscale | Command::MarianiSilver | 
+					Command::Supersample | Command::SampleReduce | Command::SamplePattern | Command::RenderMode | Command::Coloring |
+					Command::Help | Command::Size(..) | Command::SplitView | Command::Minimap | Command::Hud | Command::CopyLocation => true,
+			_ => false
+		}
+	}
 }
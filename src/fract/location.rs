@@ -0,0 +1,172 @@
+extern crate num;
+
+use std::fmt;
+use self::num::complex::{Complex, Complex64};
+use leelib::vector2::Vector2f;
+use fract::constants;
+use fract::tonecurve::ToneCurve;
+
+
+// the most iterations a location can ask for
+const MAX_ITER: u16 = 10_000;
+
+
+/**
+ * A shareable view location, which serializes to (and parses from) a single line such as:
+ *
+ *     julia x=0.1 y=-0.25 width=0.5 rot=30 iter=500 c=-0.8,0.156 tone=gamma:2.2
+ *
+ * (`rot` is in degrees, `c` only applies to julia, and `tone` is omitted for the default `ToneCurve::Bias`)
+ */
+pub struct Location {
+	pub type_id: String,  // see `FractalType::id()`
+	pub center: Vector2f,
+	pub width: f64,
+	pub rotation: f64,
+	pub max_val: u16,
+	pub julia_c: Option<Complex64>,
+	pub tone_curve: ToneCurve,
+}
+
+impl Location {
+
+	pub fn parse(s: &str) -> Result<Location, String> {
+
+		let mut tokens = s.split_whitespace();
+		let type_id = match tokens.next() {
+			Some(t) => t.to_lowercase(),
+			None => return Err("Empty location".to_string())
+		};
+
+		let mut x = None;
+		let mut y = None;
+		let mut width = None;
+		let mut rotation = 0.0;
+		let mut max_val = None;
+		let mut julia_c = None;
+		let mut tone_curve = ToneCurve::Bias;
+
+		for token in tokens {
+			let (key, value) = match token.find('=') {
+				Some(i) => (&token[..i], &token[(i + 1)..]),
+				None => return Err(format!("Expected key=value: {}", token))
+			};
+			let result = match key {
+				"x" => parse_f64(key, value).map(|v| x = Some(v)),
+				"y" => parse_f64(key, value).map(|v| y = Some(v)),
+				"width" => parse_f64(key, value).map(|v| width = Some(v)),
+				"rot" => parse_f64(key, value).map(|v| rotation = v * constants::DEG),
+				"iter" => {
+					match value.parse::<u16>() {
+						Ok(v) if v > 0 && v <= MAX_ITER => { max_val = Some(v); Ok(()) },
+						_ => Err(format!("Bad value for iter: {} (1 to {})", value, MAX_ITER))
+					}
+				},
+				"c" => {
+					let v: Vec<&str> = value.split(',').collect();
+					match (v.len(), parse_f64(key, v[0]), parse_f64(key, v[v.len() - 1])) {
+						(2, Ok(re), Ok(im)) => { julia_c = Some(Complex { re: re, im: im }); Ok(()) },
+						_ => Err(format!("Bad value for c: {} (expected re,im)", value))
+					}
+				},
+				"tone" => ToneCurve::parse(value).map(|v| tone_curve = v),
+				_ => Err(format!("Unknown key: {}", key))
+			};
+			match result {
+				Err(e) => return Err(e),
+				_ => {}
+			}
+		}
+
+		let (x, y, width) = match (x, y, width) {
+			(Some(x), Some(y), Some(w)) if w > 0.0 => (x, y, w),
+			_ => return Err("Location requires x, y and width".to_string())
+		};
+		let max_val = match max_val {
+			Some(v) => v,
+			None => return Err("Location requires iter".to_string())
+		};
+
+		Ok(Location { type_id: type_id, center: Vector2f::new(x, y), width: width, rotation: rotation,
+				max_val: max_val, julia_c: julia_c, tone_curve: tone_curve })
+	}
+}
+
+fn parse_f64(key: &str, value: &str) -> Result<f64, String> {
+	match value.parse::<f64>() {
+		Ok(v) if v.is_finite() => Ok(v),
+		_ => Err(format!("Bad value for {}: {}", key, value))
+	}
+}
+
+impl fmt::Display for Location {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		// (f64's `Display` gives the shortest string which parses back to the same value)
+		let c = match self.julia_c {
+			Some(c) => format!(" c={},{}", c.re, c.im),
+			None => "".to_string()
+		};
+		let tone = match self.tone_curve {
+			ToneCurve::Bias => "".to_string(),
+			ref t => format!(" tone={}", t)
+		};
+		write!(f, "{} x={} y={} width={} rot={} iter={}{}{}", self.type_id, self.center.x, self.center.y, self.width,
+				self.rotation / constants::DEG, self.max_val, c, tone)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn julia_location() -> Location {
+		Location {
+			type_id: "julia".to_string(),
+			center: Vector2f::new(-0.123456789012345, 0.987654321),
+			width: 1.0e-9 / 3.0,
+			rotation: 30.0 * constants::DEG,
+			max_val: 750,
+			julia_c: Some(Complex { re: -0.8, im: 0.156 }),
+			tone_curve: ToneCurve::Gamma(2.2 * 1.15 * 1.15 * 1.15),
+		}
+	}
+
+	#[test]
+	fn display_parses_back() {
+		let a = julia_location();
+		let b = Location::parse(&a.to_string()).unwrap();
+		assert_eq!(b.type_id, a.type_id);
+		assert_eq!(b.center, a.center);
+		assert_eq!(b.width, a.width);
+		// (goes thru degrees, so can be off in the last place)
+		assert!((b.rotation - a.rotation).abs() < 1e-12);
+		assert_eq!(b.max_val, a.max_val);
+		assert_eq!(b.julia_c, a.julia_c);
+		assert!(b.tone_curve == a.tone_curve);
+	}
+
+	#[test]
+	fn optional_fields() {
+		let l = Location::parse("mandelbrot x=-0.75 y=0 width=3.5 iter=500").unwrap();
+		assert_eq!(l.rotation, 0.0);
+		assert!(l.julia_c.is_none());
+		assert!(l.tone_curve == ToneCurve::Bias);
+		assert_eq!(l.to_string(), "mandelbrot x=-0.75 y=0 width=3.5 rot=0 iter=500");
+	}
+
+	#[test]
+	fn errors() {
+		assert!(Location::parse("").is_err());
+		assert!(Location::parse("mandelbrot x=0 y=0 width=1").is_err());  // no iter
+		assert!(Location::parse("mandelbrot x=0 y=0 iter=100").is_err());  // no width
+		assert!(Location::parse("mandelbrot x=0 y=0 width=0 iter=100").is_err());
+		assert!(Location::parse("mandelbrot x=0 y=0 width=1 iter=65535").is_err());
+		assert!(Location::parse("julia x=0 y=0 width=1 iter=100 c=0.5").is_err());
+		assert!(Location::parse("julia x=0 y=0 width=1 iter=100 c=0.5,0.1,0.2").is_err());
+		assert!(Location::parse("julia x=0 y=0 width=1 iter=100 c=a,b").is_err());
+		assert!(Location::parse("julia x=0 y=0 width=1 iter=100 tone=gamma:-1").is_err());
+		assert!(Location::parse("mandelbrot x=0 y=0 width=1 iter=100 zoom=2").is_err());
+		assert!(Location::parse("mandelbrot x=0 y=0 width=1 iter=100 oops").is_err());
+	}
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+
+
+pub const MAX_SEQUENCE_LENGTH: usize = 32;
+
+
+/**
+ * A sequence of 'A's and 'B's, which says which of the two growth rates
+ * the logistic map uses at each step (see `FractalCalc::get_lyapunov_value`).
+ * Fixed-size, so that it can live in `FractalSpecs`, which is `Copy`.
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Sequence {
+	is_b: [bool; MAX_SEQUENCE_LENGTH],
+	len: usize,
+}
+
+impl Sequence {
+
+	/**
+	 * Parses strings like "AB" or "AABAB" (case-insensitive)
+	 */
+	pub fn parse(s: &str) -> Result<Sequence, String> {
+		let s = s.trim();
+		if s.len() == 0 {
+			return Err("Empty sequence".to_string());
+		}
+		if s.len() > MAX_SEQUENCE_LENGTH {
+			return Err(format!("Max sequence length is {}", MAX_SEQUENCE_LENGTH));
+		}
+		let mut sequence = Sequence { is_b: [false; MAX_SEQUENCE_LENGTH], len: 0 };
+		for ch in s.chars() {
+			sequence.is_b[sequence.len] = match ch {
+				'A' | 'a' => false,
+				'B' | 'b' => true,
+				_ => return Err(format!("Unexpected '{}' (sequence must consist of A's and B's)", ch))
+			};
+			sequence.len += 1;
+		}
+		Ok(sequence)
+	}
+
+	/**
+	 * Returns the growth rate to use at step `i`
+	 */
+	pub fn rate(&self, i: usize, a: f64, b: f64) -> f64 {
+		if self.is_b[i % self.len] { b } else { a }
+	}
+}
+
+impl fmt::Display for Sequence {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let s: String = (0..self.len).map(|i| if self.is_b[i] { 'B' } else { 'A' }).collect();
+		write!(f, "{}", s)
+	}
+}
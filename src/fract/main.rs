@@ -1,13 +1,17 @@
 extern crate time;
 use std::thread;
 use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
 use std::time::Duration;
 use self::time::PreciseTime;
 use leelib::ansi;
+use leelib::terminal;
 use fract::constants;
 use fract::input;
 use fract::input::Command;
 use fract::App;
+use fract::config::Config;
+use fract::fractalcalc::CalcStats;
 
 
 /**
@@ -15,43 +19,52 @@ use fract::App;
  */
 pub fn main() {
 
-	let command = Command::None; 
-    let wrapped_command = Arc::new(Mutex::new(command));
-	let handle = input::launch_thread(wrapped_command.clone());
-    let wrapped_command = wrapped_command.clone();  // for use by main thread
+	// (must happen before the input thread takes over stdin)
+	let config = Config::load();
+	let element_ar = match config.char_aspect_ratio {
+		Some(ar) => ar,
+		None => terminal::get_cell_aspect_ratio().unwrap_or(constants::CHARACTER_ASPECT_RATIO)
+	};
+
+    let wrapped_commands = Arc::new(Mutex::new(VecDeque::new()));
+	let handle = input::launch_thread(wrapped_commands.clone());
+    let wrapped_commands = wrapped_commands.clone();  // for use by main thread
 
 	let mut timing = Timing::new(constants::TARGET_FPS);
 
-	let mut app = App::new();
+	let mut app = App::new(config, element_ar);
 
     loop {
 
         timing.frame_start();
 
+		let mut should_quit = false;
 		{
-			let mut locked_command = wrapped_command.lock().unwrap();
-			{
-				match *locked_command {
+			// handle the commands which the input thread has queued up since last frame
+			let mut locked_commands = wrapped_commands.lock().unwrap();
+			while let Some(command) = locked_commands.pop_front() {
+				match command {
 					Command::None => {},
-					Command::Quit => break, // breaks out of loop to quit program	
-					_ => {
-						// input thread changed 'command', so handle it
-						app.handle_command(&*locked_command);
-						// reset command  
-						*locked_command = Command::None;   	
-					}
-				}				
+					Command::Quit => {
+						should_quit = true;
+						break;
+					},
+					_ => app.handle_command(&command)
+				}
 			}
 		}
+		if should_quit {
+			break; // breaks out of loop to quit program
+		}
 		
 		app.update();
 
 		timing.calc_start();
-		app.calculate();
-        timing.calc_end();
+		let stats = app.calculate(timing.get_calc_budget());
+        timing.calc_end(&stats);
 
 		timing.draw_start();
-		app.draw(&timing.averages_info);
+		app.draw(&timing.averages_info, timing.avg_fps);
 		timing.draw_end();			
 
         thread::sleep(timing.get_sleep_duration());
@@ -60,6 +73,11 @@ pub fn main() {
     // quit
     print!("{}", ansi::CLEAR); 
     let _ = handle.join();
+
+    // (now that the terminal is back to normal)
+    for warning in app.config_warnings().iter() {
+    	eprintln!("{}", warning);
+    }
 } 
 
 /**
@@ -78,6 +96,7 @@ struct Timing {
 
     cum_calc_duration: i64,
     cum_draw_duration: i64, 
+    cum_calc_stats: CalcStats,
     avg_fps: f64,
     avg_calc_time: f64,
     avg_draw_time: f64,
@@ -100,6 +119,7 @@ impl Timing {
 			
 			cum_calc_duration: 0,
 			cum_draw_duration: 0,
+			cum_calc_stats: CalcStats::new(),
 			avg_fps: 0.0,
 			avg_calc_time: 0.0,
 			avg_draw_time: 0.0,
@@ -118,22 +138,32 @@ impl Timing {
             self.avg_fps = 1.0 / (usec_per_frame as f64 / 1_000_000f64);
             self.avg_calc_time = self.cum_calc_duration as f64  / self.target_fps as f64;
             self.avg_draw_time = self.cum_draw_duration as f64 / self.target_fps as f64;
-			self.averages_info = format!(" fps {:.2} calc {:.0}μs render {:.0}μs ", 
-	    		self.avg_fps, self.avg_calc_time, self.avg_draw_time);
+			
+			// percentage of calculated cells which actually got iterated (see `FractalSpecs::use_mariani_silver`)
+			let total = self.cum_calc_stats.iterated + self.cum_calc_stats.filled;
+			let iter_info = if total > 0 {
+				format!("{:.0}%", self.cum_calc_stats.iterated as f64 / total as f64 * 100.0)
+			} else {
+				"-".to_string()
+			};
+			self.averages_info = format!(" fps {:.2} calc {:.0}μs render {:.0}μs iter {} ", 
+	    		self.avg_fps, self.avg_calc_time, self.avg_draw_time, iter_info);
 
             // reset values
             self.averages_start_time = self.frame_start_time;
             self.cum_calc_duration = 0;
             self.cum_draw_duration = 0;
+            self.cum_calc_stats = CalcStats::new();
         }
 	}
 	
 	pub fn calc_start(&mut self) {
 		self.calc_start_time = PreciseTime::now();
 	}
-	pub fn calc_end(&mut self) {
+	pub fn calc_end(&mut self, stats: &CalcStats) {
 		let dur = self.calc_start_time.to(PreciseTime::now()).num_microseconds().unwrap();
-		self.cum_calc_duration += dur 
+		self.cum_calc_duration += dur;
+		self.cum_calc_stats.add(stats);
 	}
 	
 	pub fn draw_start(&mut self) {
@@ -144,6 +174,18 @@ impl Timing {
 		self.cum_draw_duration += dur 
 	}
 	
+	/**
+	 * The amount of time (in microseconds) that the fractal calculation can take up 
+	 * without making the frame go over the target frame interval, 
+	 * given the time that has already elapsed this frame and the average draw time
+	 */
+	pub fn get_calc_budget(&self) -> i64 {
+		let interval = 1_000_000 / self.target_fps as i64;
+		let elapsed = self.frame_start_time.to(PreciseTime::now()).num_microseconds().unwrap();
+		let budget = interval - elapsed - self.avg_draw_time as i64 - 1_000;  // same vague adjustment as below
+		if budget < 0 { 0 } else { budget }
+	}
+
 	/**
 	 * Calculate the sleep duration needed for program loop to update at the target_fps
 	 */
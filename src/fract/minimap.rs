@@ -0,0 +1,245 @@
+extern crate time;
+
+use std::cmp;
+use self::time::PreciseTime;
+use leelib::vector2::Vector2f;
+use leelib::matrix::Matrix;
+use fract::fractalcalc::{FractalCalc, FractalSpecs, FractalType};
+use fract::buddhabrot::{Buddhabrot, Density};
+use fract::attractor::Attractor;
+use fract::exposure::ExposureUtil;
+use fract::Asciifier;
+use fract::TextBuffer;
+
+
+// the inset's width, as a ratio of the view's (its height keeps the view's aspect ratio)
+const WIDTH_RATIO: f64 = 0.3;
+const MIN_WIDTH: usize = 12;
+
+// density-based fractals (buddhabrot, point clouds): the number of samples per inset cell,
+// which accumulate over successive frames, within a time budget per frame
+const DENSITY_SAMPLES_PER_CELL: usize = 500;
+const DENSITY_BUDGET_USEC: i64 = 3000;
+const INITIAL_BATCH_SIZE: usize = 2000;
+
+// when only the fractal's parameters have changed (eg, while a julia seed is animating),
+// the inset gets recalculated at most this often
+const MIN_RECALC_MS: i64 = 250;
+
+const VIEWPORT_CHAR: char = '•';
+const MARKER_CHAR: char = '+';
+
+
+/**
+ * An inset, drawn into the top-left corner of the view, which shows the whole fractal
+ * (ie, at its default center and width) with an outline of the current viewport.
+ *
+ * The asciified inset gets cached, and is recalculated when its size changes, when it's showing
+ * a different view or charset, or (at a limited rate) when the fractal's specs change
+ */
+pub struct Minimap {
+	pub enabled: bool,
+	chars: Matrix<char>,
+	view_index: Option<usize>,
+	charset: String,
+	specs: Option<FractalSpecs>,
+	calc_time: PreciseTime,
+
+	// density-based fractals' samples so far
+	density: Option<Density>,
+	num_samples: usize,
+	samples_per_usec: f64,  // measured from the previous batch
+	batch_count: u32,
+}
+
+impl Minimap {
+
+	pub fn new() -> Self {
+		Minimap { 
+			enabled: false, chars: Matrix::new(1, 1), view_index: None, charset: "".to_string(), specs: None,
+			calc_time: PreciseTime::now(), density: None, num_samples: 0, samples_per_usec: 0.0, batch_count: 0
+		}
+	}
+
+	/**
+	 * offset_x - the column where the view starts (nonzero for the right-hand pane of split view)
+	 * view_index - identifies the view, for caching
+	 * charset - the chars the views are using
+	 * view_w, view_h - the size of the view's matrix
+	 */
+	pub fn draw(&mut self, text_buffer: &mut TextBuffer, offset_x: usize, view_index: usize, charset: &String, specs: &FractalSpecs,
+			vp_center: Vector2f, vp_width: f64, vp_rotation: f64, view_w: usize, view_h: usize) {
+
+		let w = ((view_w as f64 * WIDTH_RATIO) as usize).max(MIN_WIDTH);
+		let h = (w * view_h / view_w.max(1)).max(1);
+		if w + 1 >= view_w || h + 1 >= view_h {
+			return;
+		}
+
+		let is_stale = match self.view_index {
+			Some(i) => i != view_index || self.chars.width() != w || self.chars.height() != h || self.charset != *charset,
+			None => true
+		};
+		let specs_changed = match self.specs {
+			Some(ref s) => *s != *specs,
+			None => true
+		};
+		let can_recalc = self.calc_time.to(PreciseTime::now()).num_milliseconds() >= MIN_RECALC_MS;
+		if is_stale || (specs_changed && can_recalc) {
+			self.start_calc(specs, charset, w, h);
+			self.view_index = Some(view_index);
+			self.charset = charset.clone();
+			self.specs = Some(*specs);
+			self.calc_time = PreciseTime::now();
+		}
+		self.continue_calc();
+
+		// inset, with a border along its inner edges
+		for y in 0..h {
+			for x in 0..w {
+				text_buffer.buffer.set(offset_x + x, y, self.chars.get(x, y));
+			}
+			text_buffer.buffer.set(offset_x + w, y, '│');
+		}
+		for x in 0..w {
+			text_buffer.buffer.set(offset_x + x, h, '─');
+		}
+		text_buffer.buffer.set(offset_x + w, h, '┘');
+
+		// viewport corners, in inset cell coordinates
+		let element_w = specs.default_width / w as f64;
+		let element_h = FractalCalc::get_height(specs, w, h, specs.default_width) / h as f64;
+		let vp_height = FractalCalc::get_height(specs, view_w, view_h, vp_width);
+		let mut corners = Vec::new();
+		for &(sx, sy) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter() {
+			let p = vp_center + Vector2f::rotate(Vector2f::new(sx * vp_width / 2.0, sy * vp_height / 2.0), vp_rotation);
+			corners.push(Vector2f::new(
+				(p.x - specs.default_center.x) / element_w + w as f64 / 2.0,
+				(p.y - specs.default_center.y) / element_h + h as f64 / 2.0));
+		}
+
+		// when the viewport is less than a couple of cells big, just mark its location
+		if (corners[2].x - corners[0].x).abs() < 2.0 && (corners[2].y - corners[0].y).abs() < 2.0 {
+			let c = (corners[0] + corners[2]) * 0.5;
+			Minimap::plot(text_buffer, offset_x, w, h, c, MARKER_CHAR);
+			return;
+		}
+
+		for i in 0..4 {
+			let a = corners[i];
+			let b = corners[(i + 1) % 4];
+			let num_steps = ((b.x - a.x).abs().max((b.y - a.y).abs()) * 2.0).ceil() as usize + 1;
+			for step in 0..(num_steps + 1) {
+				let t = step as f64 / num_steps as f64;
+				Minimap::plot(text_buffer, offset_x, w, h, a + (b - a) * t, VIEWPORT_CHAR);
+			}
+		}
+	}
+
+	fn plot(text_buffer: &mut TextBuffer, offset_x: usize, w: usize, h: usize, p: Vector2f, char: char) {
+		let x = p.x.floor();
+		let y = p.y.floor();
+		if x >= 0.0 && y >= 0.0 && x < w as f64 && y < h as f64 {
+			text_buffer.buffer.set(offset_x + x as usize, y as usize, char);
+		}
+	}
+
+	/**
+	 * Calculates the fractal at its default center and width, and asciifies it; 
+	 * density-based fractals just get started, and fill in over the following frames
+	 */
+	fn start_calc(&mut self, specs: &FractalSpecs, charset: &String, w: usize, h: usize) {
+		match specs.fractal_type {
+			FractalType::Buddhabrot(mode) => {
+				self.density = Some(Density::new(w, h, mode.num_channels()));
+			},
+			FractalType::PointCloud(..) => {
+				self.density = Some(Density::new(w, h, 1));
+			},
+			_ => {
+				self.density = None;
+				let mut matrix = Matrix::new(w, h);
+				let mut families = Matrix::new(w, h);
+				FractalCalc::write_matrix(specs, specs.default_center, specs.default_width, 0.0, &mut matrix, &mut families, 1, 0);
+				self.chars = Minimap::asciify(&matrix, &families, specs, charset);
+				return;
+			}
+		}
+		self.num_samples = 0;
+		if self.chars.width() != w || self.chars.height() != h {
+			self.chars = Matrix::new(w, h);
+		}
+		for y in 0..h {
+			for x in 0..w {
+				self.chars.set(x, y, ' ');
+			}
+		}
+	}
+
+	/**
+	 * Density-based fractals: accumulates another batch of samples (sized to fit the time budget), 
+	 * until there are enough, and re-asciifies 
+	 */
+	fn continue_calc(&mut self) {
+		let specs = match self.specs {
+			Some(s) => s,
+			None => return
+		};
+		let (w, h) = (self.chars.width(), self.chars.height());
+		let max_samples = w * h * DENSITY_SAMPLES_PER_CELL;
+		if self.num_samples >= max_samples {
+			return;
+		}
+		let density = match self.density {
+			Some(ref mut d) => d,
+			None => return
+		};
+
+		let num_samples = if self.samples_per_usec > 0.0 {
+			cmp::max((self.samples_per_usec * DENSITY_BUDGET_USEC as f64) as usize, INITIAL_BATCH_SIZE)
+		} else {
+			INITIAL_BATCH_SIZE
+		};
+		let num_samples = cmp::min(num_samples, max_samples - self.num_samples);
+		self.batch_count += 1;
+
+		let t = PreciseTime::now();
+		match specs.fractal_type {
+			FractalType::Buddhabrot(mode) => {
+				Buddhabrot::accumulate(&specs, mode, specs.default_center, specs.default_width, 0.0, density, num_samples, self.batch_count);
+			},
+			FractalType::PointCloud(system) => {
+				Attractor::accumulate(&specs, system, specs.default_center, specs.default_width, 0.0, density, num_samples, self.batch_count);
+			},
+			_ => return
+		}
+		let usec = t.to(PreciseTime::now()).num_microseconds().unwrap();
+		self.samples_per_usec = num_samples as f64 / cmp::max(usec, 1) as f64;
+		self.num_samples += num_samples;
+
+		let mut matrix = Matrix::new(w, h);
+		let mut families = Matrix::new(w, h);
+		Buddhabrot::write_matrix(density, specs.max_val, &mut matrix, &mut families);
+		self.chars = Minimap::asciify(&matrix, &families, &specs, &self.charset);
+	}
+
+	fn asciify(matrix: &Matrix<u16>, families: &Matrix<u8>, specs: &FractalSpecs, charset: &String) -> Matrix<char> {
+
+		let (w, h) = (matrix.width(), matrix.height());
+		let info = ExposureUtil::calc_for_specs(matrix, specs);
+		let mut asciifier = Asciifier::new(info.floor as f64, info.ceil as f64);
+		asciifier.set_chars(charset);
+		asciifier.set_mapping(specs.mapping());
+		asciifier.set_bias(info.bias.max(0.0));
+
+		let mut indices = Matrix::new(w, h);
+		for y in 0..h {
+			for x in 0..w {
+				indices.set(x, y, asciifier.to_char_index(matrix.get(x, y) as f64));
+			}
+		}
+		let mut chars = Matrix::new(w, h);
+		asciifier.write_textbuffer(&indices, families, &mut chars);
+		chars
+	}
+}
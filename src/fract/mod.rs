@@ -4,20 +4,58 @@ pub use self::app::App;  // 'flatten' namespace path
 pub mod asciifier;
 pub use self::asciifier::Asciifier;
 
+pub mod attractor;
+
+pub mod buddhabrot;
+
+pub mod charset;
+
+pub mod coloring;
+
+pub mod config;
+
 pub mod constants;
 
+pub mod dither;
+
+pub mod edges;
+
 pub mod coordlist;
 pub use self::coordlist::{CoordList, Three64};
 
 pub mod fractalcalc;
 
+pub mod history;
+
 pub mod exposure;
 
+pub mod formula;
+
+pub mod hud;
+
 pub mod input;
 
+pub mod location;
+
+pub mod lyapunov;
+
 pub mod main;
 
+pub mod minimap;
+
+pub mod newton;
+
+pub mod progressive;
+
+pub mod seedpath;
+
 pub mod textbuffer;
 pub use self::textbuffer::TextBuffer;
 
+pub mod tonecurve;
+
+pub mod transition;
+
+pub mod undo;
+
 pub mod view;
\ No newline at end of file
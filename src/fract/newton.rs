@@ -0,0 +1,279 @@
+extern crate num;
+
+use std::fmt;
+use self::num::complex::{Complex, Complex64};
+
+
+pub const MAX_DEGREE: usize = 8;
+
+// durand-kerner
+const ROOT_ITERATIONS: usize = 500;
+const ROOT_EPSILON: f64 = 1e-12;
+
+
+/**
+ * Polynomial with complex coefficients, along with its roots (which get found on creation).
+ * Fixed-size, so that it can live in `FractalSpecs`, which is `Copy`.
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Polynomial {
+	coeffs: [Complex64; MAX_DEGREE + 1],  // coeffs[i] is the coefficient of z^i
+	degree: usize,
+	roots: [Complex64; MAX_DEGREE],
+}
+
+impl Polynomial {
+
+	/**
+	 * Parses strings like "z^3 - 1", "z^5 + 2z^2 - 3", "2i*z^4 + (1-0.5i)z + 1"
+	 * Returns an error string if that fails, or if the polynomial's degree isn't between 2 and MAX_DEGREE
+	 */
+	pub fn parse(s: &str) -> Result<Polynomial, String> {
+
+		let s: String = s.chars().filter(|c| ! c.is_whitespace()).collect();
+		if s.len() == 0 {
+			return Err("Empty polynomial".to_string());
+		}
+
+		// split into terms at '+' and '-' (outside of parentheses), keeping the signs
+		let mut terms: Vec<String> = Vec::new();
+		let mut depth = 0;
+		let mut term = String::new();
+		for c in s.chars() {
+			match c {
+				'(' => depth += 1,
+				')' => depth -= 1,
+				'+' | '-' if depth == 0 && term.len() > 0 => {
+					terms.push(term.clone());
+					term = String::new();
+				},
+				_ => {}
+			}
+			term.push(c);
+		}
+		terms.push(term);
+
+		let mut coeffs = [Complex { re: 0.0, im: 0.0 }; MAX_DEGREE + 1];
+		for term in terms.iter() {
+			let (coeff, power) = match Polynomial::parse_term(term) {
+				Some(v) => v,
+				None => return Err(format!("Bad term: {}", term))
+			};
+			if power > MAX_DEGREE {
+				return Err(format!("Max degree is {}", MAX_DEGREE));
+			}
+			coeffs[power] = coeffs[power] + coeff;
+		}
+
+		let mut degree = 0;
+		for i in 0..(MAX_DEGREE + 1) {
+			if coeffs[i].norm_sqr() > 0.0 {
+				degree = i;
+			}
+		}
+		if degree < 2 {
+			return Err("Polynomial must be of degree 2 or more".to_string());
+		}
+
+		let mut poly = Polynomial { coeffs: coeffs, degree: degree, roots: [Complex { re: 0.0, im: 0.0 }; MAX_DEGREE] };
+		poly.find_roots(None);
+		Ok(poly)
+	}
+
+	/**
+	 * Returns the polynomial whose coefficients lie at `t` (0-1) between those of `from` and `to`.
+	 *
+	 * The root finder starts from the roots of `guess` (normally, the previous step of the same tween),
+	 * so that the roots keep their order, and basins don't swap families from one frame to the next.
+	 */
+	pub fn interpolate(from: &Polynomial, to: &Polynomial, t: f64, guess: &Polynomial) -> Polynomial {
+		let mut coeffs = [Complex { re: 0.0, im: 0.0 }; MAX_DEGREE + 1];
+		let mut degree = 0;
+		for i in 0..(MAX_DEGREE + 1) {
+			coeffs[i] = from.coeffs[i] * (1.0 - t) + to.coeffs[i] * t;
+			if coeffs[i].norm_sqr() > 0.0 {
+				degree = i;
+			}
+		}
+		if degree < 2 {
+			// coefficients cancelled out; just hang onto the previous one
+			return *guess;
+		}
+		let mut poly = Polynomial { coeffs: coeffs, degree: degree, roots: [Complex { re: 0.0, im: 0.0 }; MAX_DEGREE] };
+		poly.find_roots(Some(guess));
+		poly
+	}
+
+	/**
+	 * Returns coefficient and power of a term like "-3z^2", "+(1+2i)*z", "2.5"
+	 */
+	fn parse_term(term: &str) -> Option<(Complex64, usize)> {
+		let (coeff_str, power) = match term.find('z') {
+			Some(i) => {
+				let rest = &term[(i + 1)..];
+				let power = if rest.len() == 0 {
+					1
+				} else if rest.starts_with('^') {
+					match rest[1..].parse::<usize>() {
+						Ok(p) => p,
+						Err(_) => return None
+					}
+				} else {
+					return None;
+				};
+				(term[..i].trim_matches('*'), power)
+			},
+			None => (term, 0)
+		};
+
+		let (sign, coeff_str) = if coeff_str.starts_with('-') {
+			(-1.0, &coeff_str[1..])
+		} else if coeff_str.starts_with('+') {
+			(1.0, &coeff_str[1..])
+		} else {
+			(1.0, coeff_str)
+		};
+		if coeff_str.len() == 0 {
+			if power == 0 {
+				return None;
+			}
+			return Some((Complex { re: sign, im: 0.0 }, power));
+		}
+		match Polynomial::parse_complex(coeff_str) {
+			Some(c) => Some((c * sign, power)),
+			None => None
+		}
+	}
+
+	/**
+	 * "2", "2.5i", "i", "(1+2i)", "(-1-i)"
+	 */
+	fn parse_complex(s: &str) -> Option<Complex64> {
+		if s.starts_with('(') && s.ends_with(')') {
+			let inner = &s[1..(s.len() - 1)];
+			// split at the last sign that isn't the first char
+			let split = inner.char_indices().skip(1).filter(|&(_, c)| c == '+' || c == '-').last();
+			return match split {
+				Some((i, _)) => {
+					match (Polynomial::parse_complex(&inner[..i]), Polynomial::parse_complex(&inner[i..])) {
+						(Some(a), Some(b)) => Some(a + b),
+						_ => None
+					}
+				},
+				None => Polynomial::parse_complex(inner)
+			};
+		}
+		let (sign, s) = if s.starts_with('-') { (-1.0, &s[1..]) } else if s.starts_with('+') { (1.0, &s[1..]) } else { (1.0, s) };
+		if s.ends_with('i') {
+			let num = &s[..(s.len() - 1)];
+			let v = if num.len() == 0 { Ok(1.0) } else { num.parse::<f64>() };
+			match v {
+				Ok(v) => Some(Complex { re: 0.0, im: v * sign }),
+				Err(_) => None
+			}
+		} else {
+			match s.parse::<f64>() {
+				Ok(v) => Some(Complex { re: v * sign, im: 0.0 }),
+				Err(_) => None
+			}
+		}
+	}
+
+	pub fn roots(&self) -> &[Complex64] {
+		&self.roots[0..self.degree]
+	}
+
+	/**
+	 * Returns p(z) and p'(z) (Horner's method)
+	 */
+	pub fn eval_with_derivative(&self, z: Complex64) -> (Complex64, Complex64) {
+		let mut p = self.coeffs[self.degree];
+		let mut dp = Complex { re: 0.0, im: 0.0 };
+		for i in (0..self.degree).rev() {
+			dp = dp * z + p;
+			p = p * z + self.coeffs[i];
+		}
+		(p, dp)
+	}
+
+	/**
+	 * Durand-Kerner, on the monic version of the polynomial.
+	 * Starts from the roots of `guess` where it has them.
+	 */
+	fn find_roots(&mut self, guess: Option<&Polynomial>) {
+		let lead = self.coeffs[self.degree];
+		let mut monic = *self;
+		for i in 0..(self.degree + 1) {
+			monic.coeffs[i] = self.coeffs[i] / lead;
+		}
+
+		// conventional starting values: powers of (0.4 + 0.9i)
+		let seed = Complex { re: 0.4, im: 0.9 };
+		let mut roots = [Complex { re: 0.0, im: 0.0 }; MAX_DEGREE];
+		let mut r = Complex { re: 1.0, im: 0.0 };
+		for i in 0..self.degree {
+			roots[i] = match guess {
+				Some(g) if i < g.degree => g.roots[i],
+				_ => r
+			};
+			r = r * seed;
+		}
+
+		for _ in 0..ROOT_ITERATIONS {
+			let mut max_delta = 0.0;
+			for i in 0..self.degree {
+				let (p, _) = monic.eval_with_derivative(roots[i]);
+				let mut denom = Complex { re: 1.0, im: 0.0 };
+				for j in 0..self.degree {
+					if i != j {
+						denom = denom * (roots[i] - roots[j]);
+					}
+				}
+				if denom.norm_sqr() == 0.0 {
+					continue;
+				}
+				let delta = p / denom;
+				roots[i] = roots[i] - delta;
+				if delta.norm_sqr() > max_delta {
+					max_delta = delta.norm_sqr();
+				}
+			}
+			if max_delta < ROOT_EPSILON * ROOT_EPSILON {
+				break;
+			}
+		}
+		self.roots = roots;
+	}
+}
+
+impl fmt::Display for Polynomial {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut s = String::new();
+		for i in (0..(self.degree + 1)).rev() {
+			let c = self.coeffs[i];
+			if c.norm_sqr() == 0.0 {
+				continue;
+			}
+			let is_real = c.im == 0.0;
+			let negative = is_real && c.re < 0.0;
+			if s.len() > 0 {
+				s = s + if negative { " - " } else { " + " };
+			} else if negative {
+				s = s + "-";
+			}
+			let coeff = if is_real {
+				let v = c.re.abs();
+				if v == 1.0 && i > 0 { "".to_string() } else { format!("{}", v) }
+			} else {
+				format!("({}{:+}i)", c.re, c.im)
+			};
+			s = s + &coeff;
+			if i > 1 {
+				s = s + &format!("z^{}", i);
+			} else if i == 1 {
+				s = s + "z";
+			}
+		}
+		write!(f, "{}", s)
+	}
+}
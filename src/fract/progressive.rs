@@ -0,0 +1,260 @@
+extern crate time;
+use std::cmp::{self, min};
+use self::time::PreciseTime;
+use leelib::vector2::Vector2f;
+use leelib::matrix::Matrix;
+use fract::fractalcalc::{FractalCalc, FractalSpecs, CalcStats};
+
+
+// the step size of the first, coarse pass (ie, every 4th cell in both dimensions)
+pub const COARSE_STEP: usize = 4;
+
+// the supersample pass's band height (as a ratio of the matrix height) when there's no timing to go by yet
+const SUPERSAMPLE_BANDS: usize = 8;
+
+
+/**
+ * How the cells between the calculated cells of a coarse pass get filled in
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Upscale {
+	Nearest,
+	Bilinear,
+}
+
+
+/**
+ * Progressive refinement of a fractal matrix.
+ *
+ * When the view changes, a coarse grid is calculated first and the gaps are filled in by upscaling.
+ * Finer passes follow (each one halving the step size, and skipping the cells already calculated),
+ * for as long as the per-frame time budget allows. Whatever doesn't fit gets done on subsequent frames.
+ *
+ * Supersampling (see `FractalSpecs::supersample`) is only applied in one last pass over the whole matrix,  
+ * once the view has stopped changing and the regular passes are done. That pass gets done in bands of rows,
+ * sized to fit the time budget, and resumes on the next frame from where it left off.
+ */
+pub struct Progressive {
+	pub enabled: bool,
+	pub upscale: Upscale,
+	step: usize,  // step size of the last completed pass; 1 means fully refined
+	supersample_row: Option<usize>,  // where the supersample pass resumes from; None when there's none pending
+	last_pass_usec: i64,
+	last_pass_samples: usize,
+	stats: CalcStats,  // accumulated since the last call to `take_stats()`
+}
+
+impl Progressive {
+
+	pub fn new() -> Self {
+		Progressive {
+			enabled: true,
+			upscale: Upscale::Bilinear,
+			step: 1,
+			supersample_row: None,
+			last_pass_usec: 0,
+			last_pass_samples: 0,
+			stats: CalcStats::new(),
+		}
+	}
+
+	pub fn is_complete(&self) -> bool {
+		self.step <= 1 && self.supersample_row.is_none()
+	}
+
+	/**
+	 * Returns the stats of the passes done since the last call, and resets them
+	 */
+	pub fn take_stats(&mut self) -> CalcStats {
+		let stats = self.stats;
+		self.stats = CalcStats::new();
+		stats
+	}
+
+	/**
+	 * families
+	 *      the family matrix that goes along with `matrix` (see `CellValue`)
+	 * restart
+	 *      true when the view has changed, in which case the matrix gets recalculated from a coarse pass
+	 * budget_usec
+	 *      the time allotted for this frame; once exceeded, no further passes are started
+	 *      (but at least one pass always runs, so that a static view always gets fully refined eventually)
+	 *
+	 * Returns true if the matrix was written to
+	 */
+	pub fn render(&mut self, specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64,
+			matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, restart: bool, budget_usec: i64) -> bool {
+
+		if matrix.width() == 0 || matrix.height() == 0 {
+			return false;
+		}
+
+		let start_time = PreciseTime::now();
+		let mut did_pass = false;
+
+		// specs for the regular passes
+		let mut plain_specs = *specs;
+		plain_specs.supersample.size = 1;
+
+		if restart {
+			if self.enabled {
+				self.do_pass(&plain_specs, center, width, rotation, matrix, families, COARSE_STEP, 0, 1);
+				self.supersample_row = if specs.supersample.is_on() { Some(0) } else { None };
+			} else {
+				self.do_pass(specs, center, width, rotation, matrix, families, 1, 0, specs.supersample.size);
+				self.supersample_row = None;
+			}
+			did_pass = true;
+		}
+
+		while self.step > 1 {
+
+			let next_step = self.step / 2;
+
+			if did_pass {
+				let elapsed = start_time.to(PreciseTime::now()).num_microseconds().unwrap();
+				let estimate = self.estimate_pass_usec(matrix, next_step, self.step, 1);
+				if elapsed + estimate > budget_usec {
+					return did_pass;
+				}
+			}
+
+			let prev_step = self.step;
+			self.do_pass(&plain_specs, center, width, rotation, matrix, families, next_step, prev_step, 1);
+			did_pass = true;
+		}
+		
+		loop {
+			let row = match self.supersample_row {
+				Some(row) => row,
+				None => break
+			};
+			let n = specs.supersample.size;
+			let h = matrix.height();
+
+			// as many rows as fit in what's left of the budget (but at least one)
+			let elapsed = start_time.to(PreciseTime::now()).num_microseconds().unwrap();
+			let row_usec = self.estimate_rows_usec(matrix, 1, n);
+			if did_pass && elapsed + row_usec > budget_usec {
+				return did_pass;
+			}
+			let num_rows = if row_usec > 0 {
+				cmp::max((budget_usec - elapsed) / row_usec, 1) as usize
+			} else {
+				cmp::max(h / SUPERSAMPLE_BANDS, 1)
+			};
+			let num_rows = cmp::min(num_rows, h - row);
+
+			self.do_supersample_band(specs, center, width, rotation, matrix, families, row, num_rows);
+			self.supersample_row = if row + num_rows >= h { None } else { Some(row + num_rows) };
+			did_pass = true;
+		}
+
+		did_pass
+	}
+
+	/**
+	 * supersample_size - only used for the cost estimate of the next pass
+	 */
+	fn do_pass(&mut self, specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64,
+			matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, step: usize, prev_step: usize, supersample_size: usize) {
+
+		let t = PreciseTime::now();
+		let stats = FractalCalc::write_matrix(specs, center, width, rotation, matrix, families, step, prev_step);
+		self.stats.add(&stats);
+		if step > 1 {
+			Progressive::fill_gaps(matrix, families, step, self.upscale);
+		}
+		self.last_pass_usec = t.to(PreciseTime::now()).num_microseconds().unwrap();
+		self.last_pass_samples = Progressive::num_pass_cells(matrix, step, prev_step) * supersample_size * supersample_size;
+		self.step = step;
+	}
+
+	fn do_supersample_band(&mut self, specs: &FractalSpecs, center: Vector2f, width: f64, rotation: f64,
+			matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, start_y: usize, num_rows: usize) {
+
+		let t = PreciseTime::now();
+		let stats = FractalCalc::write_matrix_rows(specs, center, width, rotation, matrix, families, start_y, num_rows, 1, 0);
+		self.stats.add(&stats);
+		self.last_pass_usec = t.to(PreciseTime::now()).num_microseconds().unwrap();
+		let n = specs.supersample.size;
+		self.last_pass_samples = matrix.width() * num_rows * n * n;
+	}
+
+	/**
+	 * Extrapolates from the duration of the last pass, using the number of samples that need calculating
+	 */
+	fn estimate_pass_usec(&self, matrix: &Matrix<u16>, step: usize, prev_step: usize, supersample_size: usize) -> i64 {
+		if self.last_pass_samples == 0 {
+			return 0;
+		}
+		let cells = Progressive::num_pass_cells(matrix, step, prev_step) * supersample_size * supersample_size;
+		(self.last_pass_usec as f64 * cells as f64 / self.last_pass_samples as f64) as i64
+	}
+
+	/**
+	 * Like `estimate_pass_usec()`, but for the given number of full rows
+	 */
+	fn estimate_rows_usec(&self, matrix: &Matrix<u16>, num_rows: usize, supersample_size: usize) -> i64 {
+		if self.last_pass_samples == 0 {
+			return 0;
+		}
+		let cells = matrix.width() * num_rows * supersample_size * supersample_size;
+		(self.last_pass_usec as f64 * cells as f64 / self.last_pass_samples as f64) as i64
+	}
+
+	fn num_pass_cells(matrix: &Matrix<u16>, step: usize, prev_step: usize) -> usize {
+		let n = Progressive::num_grid_cells(matrix, step);
+		if prev_step > 0 {
+			n - Progressive::num_grid_cells(matrix, prev_step)
+		} else {
+			n
+		}
+	}
+
+	fn num_grid_cells(matrix: &Matrix<u16>, step: usize) -> usize {
+		if matrix.width() == 0 || matrix.height() == 0 {
+			return 0;
+		}
+		((matrix.width() - 1) / step + 1) * ((matrix.height() - 1) / step + 1)
+	}
+
+	/**
+	 * Fills in the cells which don't lie on the grid of the given step size,
+	 * using the values of the ones which do.
+	 * Families are always upscaled using 'nearest', since they can't be blended.
+	 */
+	pub fn fill_gaps(matrix: &mut Matrix<u16>, families: &mut Matrix<u8>, step: usize, upscale: Upscale) {
+
+		// the last grid column and row (cells past these have no grid cell to their right/bottom)
+		let last_x = ((matrix.width() - 1) / step) * step;
+		let last_y = ((matrix.height() - 1) / step) * step;
+
+		for y in 0..matrix.height() {
+			let y0 = y - y % step;
+			let y1 = min(y0 + step, last_y);
+			for x in 0..matrix.width() {
+				if x % step == 0 && y % step == 0 {
+					continue;
+				}
+				let x0 = x - x % step;
+				let value = match upscale {
+					Upscale::Nearest => {
+						matrix.get(x0, y0)
+					},
+					Upscale::Bilinear => {
+						let x1 = min(x0 + step, last_x);
+						let rx = if x1 > x0 { (x - x0) as f64 / (x1 - x0) as f64 } else { 0.0 };
+						let ry = if y1 > y0 { (y - y0) as f64 / (y1 - y0) as f64 } else { 0.0 };
+						let top = matrix.get(x0, y0) as f64 + (matrix.get(x1, y0) as f64 - matrix.get(x0, y0) as f64) * rx;
+						let bottom = matrix.get(x0, y1) as f64 + (matrix.get(x1, y1) as f64 - matrix.get(x0, y1) as f64) * rx;
+						(top + (bottom - top) * ry + 0.5) as u16
+					}
+				};
+				matrix.set(x, y, value);
+				let family = families.get(x0, y0);
+				families.set(x, y, family);
+			}
+		}
+	}
+}
@@ -0,0 +1,191 @@
+extern crate num;
+
+use std::f64::consts::PI;
+use self::num::complex::Complex64;
+
+
+// the main cardioid's cusp, which the cardioid and boundary paths go around
+const CUSP_RE: f64 = 0.25;
+
+// cardioid path: the cardioid's radius (from the cusp) is never treated as less than this,
+// since it goes to zero at the cusp
+const MIN_CARDIOID_RADIUS: f64 = 0.05;
+
+// boundary path: escape test iterations, the step size when searching outward along a ray,
+// and how far inside the boundary (as a ratio of the distance from the cusp) the seed stays
+const BOUNDARY_ITERATIONS: u32 = 150;
+const BOUNDARY_STEP: f64 = 0.01;
+const BOUNDARY_INSET: f64 = 0.005;
+
+// auto-orbit: the change in angle per frame, in radians
+pub const ORBIT_SPEED: f64 = 0.005;
+
+
+/**
+ * The route the julia seed takes when morphing to a new value
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum SeedPathMode {
+	// a straight line (the original behavior), which often passes thru areas where the julia set is dust
+	Straight,
+	// around the main cardioid, keeping the seed's distance from it in proportion
+	Cardioid,
+	// like `Cardioid`, but snapped to the edge of the mandelbrot set along the way
+	Boundary,
+	// a catmull-rom spline thru the seeds in the julia seed list, in order
+	Spline,
+}
+
+impl SeedPathMode {
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			SeedPathMode::Straight => "straight",
+			SeedPathMode::Cardioid => "around the cardioid",
+			SeedPathMode::Boundary => "along the mandelbrot boundary",
+			SeedPathMode::Spline => "spline thru the seed list",
+		}
+	}
+
+	pub fn next(&self) -> SeedPathMode {
+		match *self {
+			SeedPathMode::Straight => SeedPathMode::Cardioid,
+			SeedPathMode::Cardioid => SeedPathMode::Boundary,
+			SeedPathMode::Boundary => SeedPathMode::Spline,
+			SeedPathMode::Spline => SeedPathMode::Straight,
+		}
+	}
+}
+
+
+/**
+ * A route from one seed to another, as a function of progress (0 to 1)
+ */
+pub struct SeedPath {
+	mode: SeedPathMode,
+	// the start and end, with any waypoints in between;
+	// for splines, the first and last are only used for the ends' tangents
+	points: Vec<Complex64>,
+}
+
+impl SeedPath {
+
+	pub fn new(mode: SeedPathMode, from: Complex64, to: Complex64) -> SeedPath {
+		let mode = if mode == SeedPathMode::Spline { SeedPathMode::Straight } else { mode };
+		SeedPath { mode: mode, points: vec![from, to] }
+	}
+
+	/**
+	 * points - the start, any waypoints, and the end, plus an extra point at either end
+	 * which sets the direction the spline starts and ends in
+	 */
+	pub fn new_spline(points: Vec<Complex64>) -> SeedPath {
+		assert!(points.len() >= 4, "Spline needs 4 or more points");
+		SeedPath { mode: SeedPathMode::Spline, points: points }
+	}
+
+	/**
+	 * t - progress, 0 to 1
+	 */
+	pub fn point(&self, t: f64) -> Complex64 {
+		let t = t.max(0.0).min(1.0);
+		let from = self.points[0];
+		let to = self.points[self.points.len() - 1];
+		match self.mode {
+			SeedPathMode::Straight => from + (to - from) * t,
+			SeedPathMode::Cardioid => SeedPath::around_cardioid(from, to, t),
+			SeedPathMode::Boundary => {
+				// (eases on and off of the boundary at the ends)
+				let c = SeedPath::around_cardioid(from, to, t);
+				let w = 1.0 - (2.0 * t - 1.0).powi(4);
+				let edge = SeedPath::boundary_point((c - Complex64::new(CUSP_RE, 0.0)).arg());
+				c + (edge - c) * w
+			},
+			SeedPathMode::Spline => self.spline_point(t),
+		}
+	}
+
+	/**
+	 * Rotates the seed around the origin (c = r * e^(i * theta)), keeping its distance
+	 */
+	pub fn orbit(c: Complex64, radians: f64) -> Complex64 {
+		Complex64::from_polar(&c.norm(), &(c.arg() + radians))
+	}
+
+	/**
+	 * Polar coordinates around the cusp, with the radius as a ratio of the cardioid's radius at that angle.
+	 * Angles go from 0 to 2pi, so that interpolating between them goes around the cardioid, not across the cusp.
+	 */
+	fn to_cardioid_coords(c: Complex64) -> (f64, f64) {
+		let d = c - Complex64::new(CUSP_RE, 0.0);
+		let mut angle = d.arg();
+		if angle < 0.0 {
+			angle += 2.0 * PI;
+		}
+		(angle, d.norm() / SeedPath::cardioid_radius(angle))
+	}
+
+	fn from_cardioid_coords(angle: f64, ratio: f64) -> Complex64 {
+		Complex64::new(CUSP_RE, 0.0) + Complex64::from_polar(&(ratio * SeedPath::cardioid_radius(angle)), &angle)
+	}
+
+	// the main cardioid in polar form around its cusp
+	fn cardioid_radius(angle: f64) -> f64 {
+		((1.0 - angle.cos()) / 2.0).max(MIN_CARDIOID_RADIUS)
+	}
+
+	fn around_cardioid(from: Complex64, to: Complex64, t: f64) -> Complex64 {
+		let (a0, r0) = SeedPath::to_cardioid_coords(from);
+		let (a1, r1) = SeedPath::to_cardioid_coords(to);
+		let c = SeedPath::from_cardioid_coords(a0 + (a1 - a0) * t, r0 + (r1 - r0) * t);
+		// (corrects for any round-off, so that the ends are exact)
+		let e0 = from - SeedPath::from_cardioid_coords(a0, r0);
+		let e1 = to - SeedPath::from_cardioid_coords(a1, r1);
+		c + e0 * (1.0 - t) + e1 * t
+	}
+
+	/**
+	 * The first point along the ray from the cusp at `angle` which escapes, pulled back in slightly
+	 */
+	fn boundary_point(angle: f64) -> Complex64 {
+		let dir = Complex64::from_polar(&1.0, &angle);
+		let cusp = Complex64::new(CUSP_RE, 0.0);
+		let mut inner = 0.0;
+		let mut outer = BOUNDARY_STEP;
+		while outer < 3.0 && ! SeedPath::escapes(cusp + dir * outer) {
+			inner = outer;
+			outer += BOUNDARY_STEP;
+		}
+		for _ in 0..16 {
+			let mid = (inner + outer) / 2.0;
+			if SeedPath::escapes(cusp + dir * mid) { outer = mid; } else { inner = mid; }
+		}
+		cusp + dir * (inner * (1.0 - BOUNDARY_INSET))
+	}
+
+	fn escapes(c: Complex64) -> bool {
+		let mut z = Complex64::new(0.0, 0.0);
+		for _ in 0..BOUNDARY_ITERATIONS {
+			z = z * z + c;
+			if z.norm_sqr() > 4.0 {
+				return true;
+			}
+		}
+		false
+	}
+
+	/**
+	 * Uniform catmull-rom, with each span between two (non-end) points taking an equal share of t
+	 */
+	fn spline_point(&self, t: f64) -> Complex64 {
+		let p = &self.points;
+		let num_spans = p.len() - 3;
+		let f = t * num_spans as f64;
+		let i = (f.floor() as usize).min(num_spans - 1);
+		let u = f - i as f64;
+		let (p0, p1, p2, p3) = (p[i], p[i + 1], p[i + 2], p[i + 3]);
+		let u2 = u * u;
+		let u3 = u2 * u;
+		(p1 * 2.0 + (p2 - p0) * u + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * u2 + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * u3) * 0.5
+	}
+}
@@ -1,4 +1,6 @@
 extern crate num; 
+use std::cmp;
+use std::iter;
 use leelib::ansi;
 use leelib::matrix::Matrix;
 use leelib::vector2::Vector2f;
@@ -7,6 +9,9 @@ use self::num::complex::{Complex64};
 
 static HELP_TEXT: &'static str = include_str!("res/help.txt");
 
+// the width of the help text's key column (up to and including the '|')
+const HELP_KEY_WIDTH: usize = 14;
+
 
 /**
  * Keeps a buffer which is a Matrix of chars, and prints a screenful to text to stdout
@@ -49,26 +54,68 @@ impl<'a> TextBuffer<'a> {
 		}
 	}
 
+	/**
+	 * Draws lines of text as a solid block, where shorter lines get padded with spaces 
+	 * to the width of the longest one
+	 */
+	pub fn draw_text_block(&mut self, lines: &Vec<String>, x: i32, y: i32) {
+		let width = lines.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+		for i in 0..lines.len() {
+			let padding = width - lines[i].chars().count();
+			let s = lines[i].clone() + &iter::repeat(' ').take(padding).collect::<String>();
+			self.draw_string(&s, x, y + i as i32);
+		}
+	}
+
+	/**
+	 * Draws a ring centered in the buffer, which will look circular if character cells 
+	 * really do have the width/height ratio `element_ar`
+	 * 
+	 * radius: in rows
+	 */
+	pub fn draw_circle(&mut self, element_ar: f64, radius: f64, char: char) {
+		let cx = self.buffer.width() as f64 / 2.0;
+		let cy = self.buffer.height() as f64 / 2.0;
+		for y in 0..self.buffer.height() {
+			for x in 0..self.buffer.width() {
+				let dx = (x as f64 + 0.5 - cx) * element_ar;
+				let dy = y as f64 + 0.5 - cy;
+				let d = (dx * dx + dy * dy).sqrt();
+				if (d - radius).abs() <= 0.5 {
+					self.buffer.set(x, y, char);
+				}
+			}
+		}
+	}
+
+	pub fn clear(&mut self) {
+		for y in 0..self.buffer.height() {
+			for x in 0..self.buffer.width() {
+				self.buffer.set(x, y, ' ');
+			}
+		}
+	}
+
 	/**
 	 * Draw help text dialog
 	 *
 	 * offset_ratio: @0, dialog is fully visible; @1, dialog is off-screen
-	 *
-	 * TODO: make more general draw-block-of-text function
 	 */
 	pub fn draw_help_dialog(&mut self, 
 			offset_ratio: f64, vp_pos: &Vector2f, zoom: f64, julia_c: Option<Complex64> ) {
 
-		let help_text = self.help_text.clone();  // work around teh compiler :( ?!
+		let lines = self.help_lines();
 		
-		let mut y: i32 = (self.buffer.height() as i32 - self.help_text.len() as i32) / 2;  // vertically centered
-		let mut x: i32 = self.buffer.width() as i32 - help_text[0].len() as i32;  // right-justified
-		x += (help_text[0].len() as f64 * offset_ratio) as i32;
-
-		for s in help_text {
-			self.draw_string(&s.to_string(), x, y);
-			y += 1;
+		// vertically centered, or if it doesn't fit, bottom-aligned, so that the view specs stay on-screen
+		let mut y: i32 = (self.buffer.height() as i32 - lines.len() as i32) / 2;
+		if y < 0 {
+			y = self.buffer.height() as i32 - lines.len() as i32;
 		}
+		let mut x: i32 = self.buffer.width() as i32 - lines[0].len() as i32;  // right-justified
+		x += (lines[0].len() as f64 * offset_ratio) as i32;
+
+		self.draw_text_block(&lines, x, y);
+		y += lines.len() as i32;
 
 		// draw view specs
 		let mut s;
@@ -108,6 +155,51 @@ impl<'a> TextBuffer<'a> {
 		self.draw_string(&s, (x + 2), (y + 3));
 	}
 
+	/**
+	 * The help text, with its key list split into two columns when it's too tall for the buffer 
+	 * (and the buffer is wide enough)
+	 */
+	fn help_lines(&self) -> Vec<String> {
+
+		let text: Vec<String> = self.help_text.iter().map(|s| s.to_string()).collect();
+		let width = text[0].len();
+		if text.len() <= self.buffer.height() || self.buffer.width() < width * 2 {
+			return text;
+		}
+
+		// the key list lies between the 'KEYBOARD' heading (and the blank line after it) and the blank rows at the end
+		let start = match text.iter().position(|s| s.trim() == "KEYBOARD") {
+			Some(i) => i + 2,
+			None => return text
+		};
+		let mut end = text.len() - 1;
+		while end > start && text[end - 1].trim().len() == 0 {
+			end -= 1;
+		}
+		let keys = &text[start..end];
+
+		// (an entry which continues onto more lines stays in one column)
+		let mut split = (keys.len() + 1) / 2;
+		while split < keys.len() && keys[split][..HELP_KEY_WIDTH].trim() == "|" {
+			split += 1;
+		}
+
+		let blank = iter::repeat(' ').take(width).collect::<String>();
+		let widen = |s: &String| -> String {
+			if s.chars().all(|c| c == '_') { iter::repeat('_').take(width * 2).collect() } else { s.clone() + &blank }
+		};
+		let mut lines: Vec<String> = text[..start].iter().map(|s| widen(s)).collect();
+		for i in 0..cmp::max(split, keys.len() - split) {
+			let left = if i < split { keys[i].clone() } else { blank.clone() };
+			let right = if split + i < keys.len() { keys[split + i].clone() } else { blank.clone() };
+			lines.push(left + &right);
+		}
+		for s in text[end..].iter() {
+			lines.push(widen(s));
+		}
+		lines
+	}
+
 	/**
 	 * Prints a screenful of text using the buffer data, in one single pass
 	 */
@@ -0,0 +1,183 @@
+use std::fmt;
+use leelib::math;
+
+
+// `ToneCurve::Piecewise` when there's none in the config
+const DEFAULT_POINTS: [(f64, f64); 4] = [(0.0, 0.0), (0.25, 0.05), (0.6, 0.75), (1.0, 1.0)];
+
+// the amount each step of `ToneCurve::adjust()` multiplies the parameter by
+const PARAM_STEP: f64 = 1.15;
+
+
+/**
+ * Shapes the ratio (0 to 1) of a value between the asciifier's floor and ceil before it gets turned
+ * into a char index (see `Mapping::Bias`). Higher output means heavier chars.
+ */
+#[derive(Clone, PartialEq)]
+pub enum ToneCurve {
+	// blend between a sqrt and a square curve, as per the asciifier's auto-exposure 'bias' (the original behavior)
+	Bias,
+	Linear,
+	// ratio ^ (1 / gamma), so values over 1 favor heavier chars
+	Gamma(f64),
+	// ln(1 + k * ratio) / ln(1 + k), which spreads out the low values
+	Log(f64),
+	// logistic s-curve, normalized to (0,0)-(1,1); the parameter is the contrast
+	Sigmoid(f64),
+	// straight lines between points, sorted by x
+	Piecewise(Vec<(f64, f64)>),
+}
+
+impl ToneCurve {
+
+	/**
+	 * Accepts the same form as `Display`: 'bias', 'linear', 'gamma:2.2', 'log:10', 'sigmoid:8',
+	 * or 'piecewise:0,0/0.5,0.8/1,1' (or just the points)
+	 */
+	pub fn parse(s: &str) -> Result<ToneCurve, String> {
+
+		let s = s.trim().to_lowercase();
+		let (name, param) = match s.find(':') {
+			Some(i) => (&s[..i], Some(&s[(i + 1)..])),
+			None => (&s[..], None)
+		};
+
+		let curve = match (name, param) {
+			("bias", None) => ToneCurve::Bias,
+			("linear", None) => ToneCurve::Linear,
+			("gamma", Some(p)) => ToneCurve::Gamma(match ToneCurve::parse_param(p) { Ok(v) => v, Err(e) => return Err(e) }),
+			("log", Some(p)) => ToneCurve::Log(match ToneCurve::parse_param(p) { Ok(v) => v, Err(e) => return Err(e) }),
+			("sigmoid", Some(p)) => ToneCurve::Sigmoid(match ToneCurve::parse_param(p) { Ok(v) => v, Err(e) => return Err(e) }),
+			("piecewise", Some(p)) => return ToneCurve::parse_points(p),
+			(_, None) if name.contains(',') => return ToneCurve::parse_points(name),
+			_ => return Err(format!("Unknown tone curve: {}", s))
+		};
+		Ok(curve)
+	}
+
+	fn parse_param(s: &str) -> Result<f64, String> {
+		match s.parse::<f64>() {
+			Ok(v) if v > 0.0 && v.is_finite() => Ok(v),
+			_ => Err(format!("Bad tone curve parameter: {}", s))
+		}
+	}
+
+	/**
+	 * Points are 'x,y' pairs separated by '/', with x and y in the range 0 to 1
+	 */
+	fn parse_points(s: &str) -> Result<ToneCurve, String> {
+		let mut points = Vec::new();
+		for pair in s.split('/') {
+			let v: Vec<&str> = pair.split(',').collect();
+			if v.len() != 2 {
+				return Err(format!("Bad tone curve point: {}", pair));
+			}
+			match (v[0].trim().parse::<f64>(), v[1].trim().parse::<f64>()) {
+				(Ok(x), Ok(y)) if x >= 0.0 && x <= 1.0 && y >= 0.0 && y <= 1.0 => points.push((x, y)),
+				_ => return Err(format!("Bad tone curve point: {}", pair))
+			}
+		}
+		if points.len() < 2 {
+			return Err("Tone curve needs at least 2 points".to_string());
+		}
+		points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		Ok(ToneCurve::Piecewise(points))
+	}
+
+	pub fn default_piecewise() -> ToneCurve {
+		ToneCurve::Piecewise(DEFAULT_POINTS.to_vec())
+	}
+
+	/**
+	 * ratio - 0 to 1
+	 * bias - only used by `ToneCurve::Bias`
+	 */
+	pub fn apply(&self, ratio: f64, bias: f64) -> f64 {
+		match *self {
+			ToneCurve::Bias => {
+				let biased_a = ratio.sqrt();
+				let biased_b = ratio * ratio;
+				math::map(bias, -1.0, 1.0,  biased_a, biased_b)
+			},
+			ToneCurve::Linear => ratio,
+			ToneCurve::Gamma(gamma) => ratio.powf(1.0 / gamma),
+			ToneCurve::Log(k) => (1.0 + k * ratio).ln() / (1.0 + k).ln(),
+			ToneCurve::Sigmoid(contrast) => {
+				let s = |x: f64| 1.0 / (1.0 + (-contrast * (x - 0.5)).exp());
+				(s(ratio) - s(0.0)) / (s(1.0) - s(0.0))
+			},
+			ToneCurve::Piecewise(ref points) => {
+				if ratio <= points[0].0 {
+					return points[0].1;
+				}
+				for i in 1..points.len() {
+					let (x0, y0) = points[i - 1];
+					let (x1, y1) = points[i];
+					if ratio <= x1 {
+						return if x1 > x0 { math::map(ratio, x0, x1, y0, y1) } else { y1 };
+					}
+				}
+				points[points.len() - 1].1
+			}
+		}
+	}
+
+	/**
+	 * Cycles thru the kinds of curves, each with its default parameter.
+	 * piecewise - the curve to use for `ToneCurve::Piecewise` (eg, from the config)
+	 */
+	pub fn next(&self, piecewise: &ToneCurve) -> ToneCurve {
+		match *self {
+			ToneCurve::Bias => ToneCurve::Linear,
+			ToneCurve::Linear => ToneCurve::Gamma(2.2),
+			ToneCurve::Gamma(_) => ToneCurve::Log(10.0),
+			ToneCurve::Log(_) => ToneCurve::Sigmoid(8.0),
+			ToneCurve::Sigmoid(_) => piecewise.clone(),
+			ToneCurve::Piecewise(_) => ToneCurve::Bias,
+		}
+	}
+
+	/**
+	 * Steps the curve's parameter up or down (by `steps`), within a sensible range.
+	 * Returns false if the curve has no parameter.
+	 */
+	pub fn adjust(&mut self, steps: i32) -> bool {
+		let m = PARAM_STEP.powi(steps);
+		match *self {
+			ToneCurve::Gamma(ref mut gamma) => *gamma = (*gamma * m).max(0.1).min(10.0),
+			ToneCurve::Log(ref mut k) => *k = (*k * m).max(0.1).min(10_000.0),
+			ToneCurve::Sigmoid(ref mut contrast) => *contrast = (*contrast * m).max(0.5).min(50.0),
+			_ => return false
+		}
+		true
+	}
+
+	/**
+	 * Like `Display`, but with the parameter rounded, for showing to the user 
+	 * (`Display` keeps full precision, so that it parses back to the same curve)
+	 */
+	pub fn label(&self) -> String {
+		match *self {
+			ToneCurve::Gamma(gamma) => format!("gamma:{:.2}", gamma),
+			ToneCurve::Log(k) => format!("log:{:.2}", k),
+			ToneCurve::Sigmoid(contrast) => format!("sigmoid:{:.2}", contrast),
+			ref t => format!("{}", t)
+		}
+	}
+}
+
+impl fmt::Display for ToneCurve {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			ToneCurve::Bias => write!(f, "bias"),
+			ToneCurve::Linear => write!(f, "linear"),
+			ToneCurve::Gamma(gamma) => write!(f, "gamma:{}", gamma),
+			ToneCurve::Log(k) => write!(f, "log:{}", k),
+			ToneCurve::Sigmoid(contrast) => write!(f, "sigmoid:{}", contrast),
+			ToneCurve::Piecewise(ref points) => {
+				let v: Vec<String> = points.iter().map(|&(x, y)| format!("{},{}", x, y)).collect();
+				write!(f, "piecewise:{}", v.join("/"))
+			}
+		}
+	}
+}
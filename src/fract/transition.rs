@@ -0,0 +1,201 @@
+extern crate time;
+use self::time::PreciseTime;
+use leelib::matrix::Matrix;
+use leelib::vector2::Vector2f;
+use fract::asciifier::Asciifier;
+use fract::fractalcalc::FractalCalc;
+
+
+// how long a transition takes
+const DURATION_MS: i64 = 700;
+
+// zoom-through: how far the view zooms out before swapping, as a multiple of its width
+const ZOOM_OUT: f64 = 12.0;
+
+// wipe: the width of its ragged edge, as a ratio of the screen width
+const WIPE_EDGE: f64 = 0.15;
+
+
+/**
+ * How one frame gets replaced by the next when switching views, jumping to a location,
+ * or changing the charset or coloring
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub enum TransitionStyle {
+	// interpolates between the two frames' char indices, as ratios of their charsets
+	Crossfade,
+	// left to right
+	Wipe,
+	// a circle which opens from the center
+	Iris,
+	// cells change over in random order
+	Dissolve,
+	// zooms out, swaps, and zooms back in (needs a `ViewPosition` for each side)
+	ZoomThrough,
+}
+
+impl TransitionStyle {
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			TransitionStyle::Crossfade => "crossfade",
+			TransitionStyle::Wipe => "wipe",
+			TransitionStyle::Iris => "iris",
+			TransitionStyle::Dissolve => "dissolve",
+			TransitionStyle::ZoomThrough => "zoom-through",
+		}
+	}
+
+	pub fn next(&self) -> TransitionStyle {
+		match *self {
+			TransitionStyle::Crossfade => TransitionStyle::Wipe,
+			TransitionStyle::Wipe => TransitionStyle::Iris,
+			TransitionStyle::Iris => TransitionStyle::Dissolve,
+			TransitionStyle::Dissolve => TransitionStyle::ZoomThrough,
+			TransitionStyle::ZoomThrough => TransitionStyle::Crossfade,
+		}
+	}
+}
+
+
+/**
+ * Which view, and where in it
+ */
+#[derive(Clone, Copy)]
+pub struct ViewPosition {
+	pub index: usize,
+	pub center: Vector2f,
+	pub width: f64,
+	pub rotation: f64,
+}
+
+
+/**
+ * A transition in progress. Holds a snapshot of the outgoing frame, and is driven by the time
+ * since it started rather than by the frame count.
+ */
+pub struct Transition {
+	pub style: TransitionStyle,
+	// zoom-through: the outgoing and incoming positions
+	pub zoom: Option<(ViewPosition, ViewPosition)>,
+	start_time: PreciseTime,
+	from_chars: Matrix<char>,
+	from_indices: Matrix<u8>,
+	from_num_chars: usize,
+	mix: Matrix<u8>,  // crossfade's in-between indices
+	seed: usize,  // dissolve's cell order
+}
+
+impl Transition {
+
+	/**
+	 * Takes the snapshot of the outgoing frame from the given view's state
+	 * seed - should be different each time, so that each dissolve is different
+	 */
+	pub fn new(style: TransitionStyle, asciifier: &Asciifier, index_matrix: &Matrix<u8>, family_matrix: &Matrix<u8>,
+			edge_matrix: &Matrix<char>, seed: usize) -> Transition {
+		let mut from_chars = Matrix::new(index_matrix.width(), index_matrix.height());
+		asciifier.write_textbuffer_at(index_matrix, family_matrix, Some(edge_matrix), &mut from_chars, 0);
+		Transition {
+			style: style,
+			zoom: None,
+			start_time: PreciseTime::now(),
+			from_chars: from_chars,
+			from_indices: index_matrix.clone(),
+			from_num_chars: asciifier.chars().len(),
+			mix: Matrix::new(index_matrix.width(), index_matrix.height()),
+			seed: seed,
+		}
+	}
+
+	/**
+	 * 0 to 1
+	 */
+	pub fn progress(&self) -> f64 {
+		let ms = self.start_time.to(PreciseTime::now()).num_milliseconds();
+		(ms as f64 / DURATION_MS as f64).max(0.0).min(1.0)
+	}
+
+	pub fn is_complete(&self) -> bool {
+		self.progress() >= 1.0
+	}
+
+	/**
+	 * Whether the snapshot can be drawn over a view of the given size
+	 */
+	pub fn fits(&self, w: usize, h: usize) -> bool {
+		self.from_chars.width() == w && self.from_chars.height() == h
+	}
+
+	/**
+	 * Zoom-through: what to multiply the view's width by, which rises to `ZOOM_OUT` halfway thru and falls back to 1
+	 */
+	pub fn zoom_multiplier(&self) -> f64 {
+		let p = self.progress();
+		let d = 1.0 - (p * 2.0 - 1.0).abs();
+		ZOOM_OUT.powf(Transition::ease(d))
+	}
+
+	/**
+	 * Writes the in-between frame, from the snapshot and the incoming view's state
+	 * element_ar - the width/height ratio of a character cell, which keeps the iris round
+	 */
+	pub fn write_textbuffer(&mut self, asciifier: &Asciifier, index_matrix: &Matrix<u8>, family_matrix: &Matrix<u8>,
+			edge_matrix: &Matrix<char>, element_ar: f64, text_buffer: &mut Matrix<char>) {
+
+		let t = Transition::ease(self.progress());
+		match self.style {
+			TransitionStyle::Crossfade => {
+				let max1 = (self.from_num_chars - 1) as u8;
+				let max2 = (asciifier.chars().len() - 1) as u8;
+				Matrix::interpolate2(t, &self.from_indices, max1, index_matrix, max2, &mut self.mix);
+				asciifier.write_textbuffer(&self.mix, family_matrix, text_buffer);
+			},
+			TransitionStyle::ZoomThrough => {
+				// (the zooming is done by the app; this just draws the view)
+				asciifier.write_textbuffer_at(index_matrix, family_matrix, Some(edge_matrix), text_buffer, 0);
+			},
+			_ => {
+				// cells whose threshold hasn't been reached yet keep the outgoing frame's char
+				asciifier.write_textbuffer_at(index_matrix, family_matrix, Some(edge_matrix), text_buffer, 0);
+				let w = self.from_chars.width().min(text_buffer.width());
+				let h = self.from_chars.height().min(text_buffer.height());
+				for y in 0..h {
+					for x in 0..w {
+						if self.threshold(x, y, element_ar) > t {
+							text_buffer.set(x, y, self.from_chars.get(x, y));
+						}
+					}
+				}
+			}
+		}
+	}
+
+	/**
+	 * The progress (0 to 1) at which the cell changes over
+	 */
+	fn threshold(&self, x: usize, y: usize, element_ar: f64) -> f64 {
+		let w = self.from_chars.width() as f64;
+		let h = self.from_chars.height() as f64;
+		let random = FractalCalc::hash(x, y, self.seed) as f64 / 4294967296.0;
+		match self.style {
+			TransitionStyle::Wipe => {
+				(x as f64 + 0.5) / w * (1.0 - WIPE_EDGE) + random * WIPE_EDGE
+			},
+			TransitionStyle::Iris => {
+				// (in units of cell widths)
+				let dx = x as f64 + 0.5 - w / 2.0;
+				let dy = (y as f64 + 0.5 - h / 2.0) / element_ar;
+				let max = ((w / 2.0) * (w / 2.0) + (h / 2.0 / element_ar) * (h / 2.0 / element_ar)).sqrt();
+				(dx * dx + dy * dy).sqrt() / max
+			},
+			TransitionStyle::Dissolve => random,
+			_ => 0.0
+		}
+	}
+
+	// smoothstep
+	fn ease(t: f64) -> f64 {
+		t * t * (3.0 - 2.0 * t)
+	}
+}
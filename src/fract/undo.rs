@@ -0,0 +1,99 @@
+extern crate num;
+
+use std::collections::VecDeque;
+use self::num::complex::Complex64;
+use leelib::vector2::Vector2f;
+use fract::exposure::ExposureMode;
+
+
+// the most states kept (the oldest get dropped)
+const CAPACITY: usize = 32;
+
+
+/**
+ * What an undo restores
+ */
+#[derive(Clone, Copy)]
+pub struct UndoState {
+	pub view_index: usize,
+	pub center: Vector2f,
+	pub width: f64,
+	pub rotation: f64,
+	pub julia_c: Option<Complex64>,
+	pub use_exposure: bool,
+	pub exposure_mode: ExposureMode,
+}
+
+impl UndoState {
+
+	fn is_same(&self, other: &UndoState) -> bool {
+		self.view_index == other.view_index && self.center.x == other.center.x && self.center.y == other.center.y
+				&& self.width == other.width && self.rotation == other.rotation && self.julia_c == other.julia_c
+				&& self.use_exposure == other.use_exposure && self.exposure_mode == other.exposure_mode
+	}
+}
+
+
+/**
+ * Bounded ring buffer of states to undo to, plus the states that undoing has gone back from
+ */
+pub struct UndoStack {
+	states: VecDeque<UndoState>,
+	redo_states: Vec<UndoState>,
+}
+
+impl UndoStack {
+
+	pub fn new() -> UndoStack {
+		UndoStack { states: VecDeque::with_capacity(CAPACITY), redo_states: Vec::new() }
+	}
+
+	/**
+	 * Saves the state from before a change. Clears the redo states.
+	 */
+	pub fn push(&mut self, state: UndoState) {
+		self.redo_states.clear();
+		match self.states.back() {
+			Some(last) if last.is_same(&state) => return,
+			_ => {}
+		}
+		if self.states.len() >= CAPACITY {
+			self.states.pop_front();
+		}
+		self.states.push_back(state);
+	}
+
+	/**
+	 * current - the state being undone, which redo then goes back to
+	 */
+	pub fn undo(&mut self, current: UndoState) -> Option<UndoState> {
+		match self.states.pop_back() {
+			Some(state) => {
+				self.redo_states.push(current);
+				Some(state)
+			},
+			None => None
+		}
+	}
+
+	pub fn redo(&mut self, current: UndoState) -> Option<UndoState> {
+		match self.redo_states.pop() {
+			Some(state) => {
+				if self.states.len() >= CAPACITY {
+					self.states.pop_front();
+				}
+				self.states.push_back(current);
+				Some(state)
+			},
+			None => None
+		}
+	}
+
+	pub fn undo_len(&self) -> usize {
+		self.states.len()
+	}
+
+	pub fn redo_len(&self) -> usize {
+		self.redo_states.len()
+	}
+}
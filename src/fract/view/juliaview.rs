@@ -9,8 +9,10 @@ use leelib::dirtychecker::DirtyChecker;
 use fract::constants;
 use fract::fractalcalc::{FractalSpecs, FractalType};
 use fract::Asciifier;
-use fract::exposure::{ExposureInfo};
+use fract::exposure::{ExposureInfo, ExposureMode, CurveAnimator};
+use fract::progressive::Progressive;
 use fract::CoordList;
+use fract::seedpath::{SeedPath, SeedPathMode, ORBIT_SPEED};
 use fract::view::View;
 
 
@@ -20,17 +22,23 @@ pub struct JuliaView  {
 	specs: FractalSpecs,
     asciifier: Asciifier,
     fractal_matrix: Matrix<u16>,
+    family_matrix: Matrix<u8>,
+    edge_matrix: Matrix<char>,
     index_matrix: Matrix<u8>,
 
 	position_animator: Animator<Vector2f>,
 	width_animator: Animator<f64>,
 	rotation_animator: Animator<f64>, 
+	progressive: Progressive,
 
     exposure_info: ExposureInfo, 
 	exposure_floor_animator: Animator<f64>,
 	exposure_ceil_animator: Animator<f64>,
 	dirty_exposure_checker: DirtyChecker,
 	use_exposure: bool,
+	exposure_mode: ExposureMode,
+	exposure_curve: CurveAnimator,
+	exposure_lock: Option<(f64, f64)>,
 
 	coord_anim_index: usize,
 	coord_anim_phase: u8,
@@ -39,7 +47,13 @@ pub struct JuliaView  {
 	
 	// struct-specific members:
 	julia_coordlist: CoordList<Complex64>,
-	julia_coord_animator: Animator<Vector2f>,
+	// progress along seed_path, 0 to 1
+	julia_coord_animator: Animator<f64>,
+	seed_path: SeedPath,
+	seed_path_mode: SeedPathMode,
+	// the seed list entry the seed is at (or heading to), if any
+	seed_index: Option<usize>,
+	is_orbiting: bool,
 	dirty_fractal_checker: DirtyChecker, 
 }
 
@@ -52,18 +66,24 @@ impl JuliaView {
 			specs: specs,
 		    asciifier: Asciifier::new(0.0, specs.max_val as f64),
 		    fractal_matrix: Matrix::new(matrix_w, matrix_h),
+		    family_matrix: Matrix::new(matrix_w, matrix_h),
+		    edge_matrix: Matrix::new(matrix_w, matrix_h),
 		    index_matrix: Matrix::new(matrix_w, matrix_h),
 
 			position_animator: Animator::<Vector2f>::new(Vector2f::new(0.0, 0.0), Anim::None),
 			width_animator: Animator::<f64>::new(specs.default_width, Anim::None),
 			rotation_animator: Animator::<f64>::new(0.0, Anim::None),
+			progressive: Progressive::new(),
 			dirty_fractal_checker: DirtyChecker::new(6),  // note, 2 more vals than mandelbrot version
 
 			exposure_floor_animator: Animator::<f64>::new( 0.0, Anim::Target { target: 0.0, coefficient: 0.1, epsilon: Some(0.01) } ),
 			exposure_ceil_animator: Animator::<f64>::new( specs.max_val as f64, Anim::Target { target: specs.max_val as f64, coefficient: 0.1, epsilon: Some(0.01) } ),
-			exposure_info: ExposureInfo { floor: 0, ceil: specs.max_val as usize, bias: 0.0 },
+			exposure_info: ExposureInfo { floor: 0, ceil: specs.max_val as usize, bias: 0.0, curve: Vec::new() },
 			dirty_exposure_checker: DirtyChecker::new(2),
 			use_exposure: true,
+			exposure_mode: ExposureMode::Heuristic,
+			exposure_curve: CurveAnimator::new(),
+			exposure_lock: None,
 
 			coord_anim_phase: 0,
 			coord_anim_index: 0,
@@ -71,26 +91,62 @@ impl JuliaView {
 			debug: "".to_string(),
 
 			julia_coordlist: CoordList::<Complex64>::new(constants::JULIA_COMPLEX_TEXT),
-			julia_coord_animator: Animator::<Vector2f>::new( Vector2f { x: 0.0, y: 0.0 }, Anim::None ),
+			julia_coord_animator: Animator::<f64>::new(1.0, Anim::None),
+			seed_path: SeedPath::new(SeedPathMode::Straight, Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0)),
+			seed_path_mode: SeedPathMode::Straight,
+			seed_index: Some(1),  // (see `App::new()`)
+			is_orbiting: false,
 		}
 	}
 
 	/**
-	 * Tweens julia seed coordlist using julia_coord_animator
+	 * Tweens julia seed coordlist using julia_coord_animator, along a path as per seed_path_mode
 	 */
 	fn start_julia_coord_animator(&mut self, current: Complex64, index: usize) {
+		let target = self.julia_coordlist.get(index);
+		let path = match (self.seed_path_mode, self.seed_index) {
+			(SeedPathMode::Spline, Some(from_index)) if from_index != index => self.spline_path(current, from_index, index),
+			(mode, _) => SeedPath::new(mode, current, target)
+		};
+		self.start_julia_coord_animator_on(path, index);
+		self.seed_index = Some(index);
+	}
+
+	/**
+	 * index - the coordlist index being animated to, if any
+	 */
+	fn start_julia_coord_animator_on(&mut self, path: SeedPath, index: usize) {
 		
 		self.coord_anim_phase = 1;
 		self.coord_anim_index = index;
+		self.is_orbiting = false;
 
-		let current2 = Vector2f { x: current.re, y: current.im };  // convert for Animator
-		self.julia_coord_animator.value = current2;
-		
-		let target = self.julia_coordlist.get(index);
-		let target2 = Vector2f { x: target.re, y: target.im };  // convert for Animator
-		let anim = Anim::Target { target: target2, coefficient: constants::TARGET_COEF * 1.0, epsilon: None };   
+		self.seed_path = path;
+		self.julia_coord_animator.value = 0.0;
+		let anim = Anim::Target { target: 1.0, coefficient: constants::TARGET_COEF * 1.0, epsilon: Some(0.0001) };   
 		self.julia_coord_animator.set_anim(anim);  
 	}
+
+	/**
+	 * Goes thru each of the seed list's entries between the two indices, in order.
+	 * The ends' tangents aim at the entries just outside of the range (if any).
+	 */
+	fn spline_path(&self, current: Complex64, from_index: usize, to_index: usize) -> SeedPath {
+		let n = self.julia_coordlist.len() as i32;
+		let step: i32 = if to_index > from_index { 1 } else { -1 };
+		let get = |i: i32| self.julia_coordlist.get(i.max(0).min(n - 1) as usize);
+		let (from, to) = (from_index as i32, to_index as i32);
+
+		let mut points = vec![get(from - step), current];
+		let mut i = from + step;
+		while i != to {
+			points.push(get(i));
+			i += step;
+		}
+		points.push(get(to));
+		points.push(get(to + step));
+		SeedPath::new_spline(points)
+	}
 }
 
 impl View for JuliaView {
@@ -108,6 +164,18 @@ impl View for JuliaView {
 	fn fractal_matrix_m(&mut self) -> &mut Matrix<u16> {
 		&mut self.fractal_matrix
 	}
+	fn family_matrix(&self) -> &Matrix<u8> {
+		&self.family_matrix
+	}
+	fn family_matrix_m(&mut self) -> &mut Matrix<u8> {
+		&mut self.family_matrix
+	}
+	fn edge_matrix(&self) -> &Matrix<char> {
+		&self.edge_matrix
+	}
+	fn edge_matrix_m(&mut self) -> &mut Matrix<char> {
+		&mut self.edge_matrix
+	}
 
     fn index_matrix(&self) -> &Matrix<u8> {
     	&self.index_matrix
@@ -118,6 +186,8 @@ impl View for JuliaView {
 
 	fn set_matrix_size(&mut self, matrix_w: usize, matrix_h: usize) {
 	    self.fractal_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.family_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.edge_matrix = Matrix::new(matrix_w, matrix_h);
 	    self.index_matrix = Matrix::new(matrix_w, matrix_h);
 	    self.dirty_fractal_checker().force_dirty();
 	}
@@ -141,6 +211,12 @@ impl View for JuliaView {
 	fn dirty_fractal_checker(&mut self) -> &mut DirtyChecker {
 		&mut self.dirty_fractal_checker
 	}
+	fn progressive(&self) -> &Progressive {
+		&self.progressive
+	}
+	fn progressive_m(&mut self) -> &mut Progressive {
+		&mut self.progressive
+	}
 
 	fn use_exposure(&self) -> bool {
 		self.use_exposure
@@ -163,6 +239,24 @@ impl View for JuliaView {
 	fn dirty_exposure_checker(&mut self) -> &mut DirtyChecker {
 		&mut self.dirty_exposure_checker
 	}
+	fn exposure_mode(&self) -> ExposureMode {
+		self.exposure_mode
+	}
+	fn set_exposure_mode(&mut self, mode: ExposureMode) {
+		self.exposure_mode = mode;
+	}
+	fn exposure_curve(&self) -> &CurveAnimator {
+		&self.exposure_curve
+	}
+	fn exposure_curve_m(&mut self) -> &mut CurveAnimator {
+		&mut self.exposure_curve
+	}
+	fn exposure_lock(&self) -> Option<(f64, f64)> {
+		self.exposure_lock
+	}
+	fn set_exposure_lock(&mut self, lock: Option<(f64, f64)>) {
+		self.exposure_lock = lock;
+	}
 	
 	fn coord_anim_phase(&self) -> u8 {
 		self.coord_anim_phase
@@ -207,13 +301,14 @@ impl View for JuliaView {
 		
 		match self.specs.fractal_type {
 			FractalType::Julia(ref mut c) => {
-				if self.coord_anim_phase == 1 {
+				if self.is_orbiting {
+					*c = SeedPath::orbit(*c, ORBIT_SPEED);
+				} else if self.coord_anim_phase == 1 {
 					match self.julia_coord_animator.anim() {
 						&Anim::Target { .. } => {
 							// update julia anim, and copy over value 
 							self.julia_coord_animator.update();
-							c.re = self.julia_coord_animator.value.x;
-							c.im = self.julia_coord_animator.value.y;
+							*c = self.seed_path.point(self.julia_coord_animator.value);
 						},
 						_ => { }
 					}
@@ -223,10 +318,43 @@ impl View for JuliaView {
 		}		
 	}
 	
+	fn tween_julia_c(&mut self, c: Complex64) {
+		match self.specs.fractal_type {
+			FractalType::Julia(current) => {
+				// (not a coordlist index, so that any digit key can start a new coord anim)
+				let index = self.julia_coordlist.len();
+				let path = SeedPath::new(self.seed_path_mode, current, c);
+				self.start_julia_coord_animator_on(path, index);
+				self.seed_index = None;
+			},
+			_ => { }
+		}
+	}
+
+	fn seed_path_mode(&self) -> SeedPathMode {
+		self.seed_path_mode
+	}
+	fn set_seed_path_mode(&mut self, mode: SeedPathMode) {
+		self.seed_path_mode = mode;
+	}
+	fn is_orbiting(&self) -> bool {
+		self.is_orbiting
+	}
+	fn set_orbiting(&mut self, b: bool) {
+		self.is_orbiting = b;
+		if b {
+			self.julia_coord_animator.set_anim(Anim::None);
+			self.seed_index = None;
+		}
+	}
+
 	fn do_dirty_fractal_check(&mut self) -> bool{
-		let v = vec![self.position_animator.value.x, self.position_animator.value.x, 
-			self.width_animator.value, self.rotation_animator.value,
-			self.julia_coord_animator.value.x, self.julia_coord_animator.value.y];
+		let (re, im) = match self.specs.fractal_type {
+			FractalType::Julia(c) => (c.re, c.im),
+			_ => (0.0, 0.0)
+		};
+		let v = vec![self.position_animator.value.x, self.position_animator.value.y, 
+			self.width_animator.value, self.rotation_animator.value, re, im];
 		self.dirty_fractal_checker.do_check(v)
 	}
 }
@@ -0,0 +1,243 @@
+extern crate num;
+extern crate num_cpus;
+
+use leelib::vector2::Vector2f;
+use leelib::matrix::Matrix;
+use leelib::animator::{Animator, Anim};
+use leelib::dirtychecker::DirtyChecker;
+use fract::constants;
+use fract::fractalcalc::{FractalSpecs, FractalType};
+use fract::Asciifier;
+use fract::exposure::{ExposureInfo, ExposureMode, CurveAnimator};
+use fract::progressive::Progressive;
+use fract::lyapunov::Sequence;
+use fract::CoordList;
+use fract::view::View;
+
+
+/**
+ * Lyapunov exponents of the logistic map, for the sequence in `FractalSpecs::fractal_type`
+ */
+pub struct LyapunovView  {
+
+	// members backed by trait getter/setters:
+	specs: FractalSpecs,
+    asciifier: Asciifier,
+    fractal_matrix: Matrix<u16>,
+    family_matrix: Matrix<u8>,
+    edge_matrix: Matrix<char>,
+    index_matrix: Matrix<u8>,
+
+	position_animator: Animator<Vector2f>,
+	width_animator: Animator<f64>,
+	rotation_animator: Animator<f64>,
+	progressive: Progressive,
+
+    exposure_info: ExposureInfo,
+	exposure_floor_animator: Animator<f64>,
+	exposure_ceil_animator: Animator<f64>,
+	dirty_exposure_checker: DirtyChecker,
+	use_exposure: bool,
+	exposure_mode: ExposureMode,
+	exposure_curve: CurveAnimator,
+	exposure_lock: Option<(f64, f64)>,
+
+	coord_anim_index: usize,
+	coord_anim_phase: u8,
+
+	debug:String,
+
+	// struct-specific members:
+	sequence_list: CoordList<Sequence>,
+	dirty_fractal_checker: DirtyChecker,
+}
+
+impl LyapunovView {
+
+	pub fn new(matrix_w: usize, matrix_h: usize, specs: FractalSpecs) -> Self {
+
+		LyapunovView {
+
+			specs: specs,
+		    asciifier: Asciifier::new(0.0, specs.max_val as f64),
+		    fractal_matrix: Matrix::new(matrix_w, matrix_h),
+		    family_matrix: Matrix::new(matrix_w, matrix_h),
+		    edge_matrix: Matrix::new(matrix_w, matrix_h),
+		    index_matrix: Matrix::new(matrix_w, matrix_h),
+
+			position_animator: Animator::<Vector2f>::new(specs.default_center, Anim::None),
+			width_animator: Animator::<f64>::new(specs.default_width, Anim::None),
+			rotation_animator: Animator::<f64>::new(0.0, Anim::None),
+			progressive: Progressive::new(),
+			dirty_fractal_checker: DirtyChecker::new(4),
+
+			exposure_floor_animator: Animator::<f64>::new( 0.0, Anim::Target { target: 0.0, coefficient: 0.1, epsilon: Some(0.01) } ),
+			exposure_ceil_animator: Animator::<f64>::new( specs.max_val as f64, Anim::Target { target: specs.max_val as f64, coefficient: 0.1, epsilon: Some(0.01) } ),
+			exposure_info: ExposureInfo { floor: 0, ceil: specs.max_val as usize, bias: 0.0, curve: Vec::new() },
+			dirty_exposure_checker: DirtyChecker::new(2),
+			use_exposure: true,
+			exposure_mode: ExposureMode::Heuristic,
+			exposure_curve: CurveAnimator::new(),
+			exposure_lock: None,
+
+			coord_anim_phase: 0,
+			coord_anim_index: 0,
+
+			debug: "".to_string(),
+
+			sequence_list: CoordList::<Sequence>::new(constants::LYAPUNOV_SEQUENCES_TEXT),
+		}
+	}
+}
+
+impl View for LyapunovView {
+
+	fn specs(&self) -> &FractalSpecs {
+		&self.specs
+	}
+	fn specs_m(&mut self) -> &mut FractalSpecs {
+		&mut self.specs
+	}
+
+	fn fractal_matrix(&self) -> &Matrix<u16> {
+		&self.fractal_matrix
+	}
+	fn fractal_matrix_m(&mut self) -> &mut Matrix<u16> {
+		&mut self.fractal_matrix
+	}
+	fn family_matrix(&self) -> &Matrix<u8> {
+		&self.family_matrix
+	}
+	fn family_matrix_m(&mut self) -> &mut Matrix<u8> {
+		&mut self.family_matrix
+	}
+	fn edge_matrix(&self) -> &Matrix<char> {
+		&self.edge_matrix
+	}
+	fn edge_matrix_m(&mut self) -> &mut Matrix<char> {
+		&mut self.edge_matrix
+	}
+
+    fn index_matrix(&self) -> &Matrix<u8> {
+    	&self.index_matrix
+    }
+    fn index_matrix_m(&mut self) -> &mut Matrix<u8> {
+       	&mut self.index_matrix
+    }
+
+	fn set_matrix_size(&mut self, matrix_w: usize, matrix_h: usize) {
+	    self.fractal_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.family_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.edge_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.index_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.dirty_fractal_checker().force_dirty();
+	}
+
+    fn asciifier(&self) -> &Asciifier {
+    	&self.asciifier
+    }
+	fn asciifier_m(&mut self) -> &mut Asciifier {
+		&mut self.asciifier
+	}
+
+	fn position_animator(&mut self) -> &mut Animator<Vector2f> {
+		&mut self.position_animator
+	}
+	fn width_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.width_animator
+	}
+	fn rotation_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.rotation_animator
+	}
+	fn dirty_fractal_checker(&mut self) -> &mut DirtyChecker {
+		&mut self.dirty_fractal_checker
+	}
+	fn progressive(&self) -> &Progressive {
+		&self.progressive
+	}
+	fn progressive_m(&mut self) -> &mut Progressive {
+		&mut self.progressive
+	}
+
+	fn use_exposure(&self) -> bool {
+		self.use_exposure
+	}
+	fn toggle_use_exposure(&mut self) {
+		self.use_exposure = ! self.use_exposure;
+	}
+	fn exposure_info(&self) -> &ExposureInfo {
+		&self.exposure_info
+	}
+	fn set_exposure_info(&mut self, info: ExposureInfo) {
+		self.exposure_info = info;
+	}
+	fn exposure_floor_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.exposure_floor_animator
+	}
+	fn exposure_ceil_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.exposure_ceil_animator
+	}
+	fn dirty_exposure_checker(&mut self) -> &mut DirtyChecker {
+		&mut self.dirty_exposure_checker
+	}
+	fn exposure_mode(&self) -> ExposureMode {
+		self.exposure_mode
+	}
+	fn set_exposure_mode(&mut self, mode: ExposureMode) {
+		self.exposure_mode = mode;
+	}
+	fn exposure_curve(&self) -> &CurveAnimator {
+		&self.exposure_curve
+	}
+	fn exposure_curve_m(&mut self) -> &mut CurveAnimator {
+		&mut self.exposure_curve
+	}
+	fn exposure_lock(&self) -> Option<(f64, f64)> {
+		self.exposure_lock
+	}
+	fn set_exposure_lock(&mut self, lock: Option<(f64, f64)>) {
+		self.exposure_lock = lock;
+	}
+
+	fn coord_anim_phase(&self) -> u8 {
+		self.coord_anim_phase
+	}
+	fn set_coord_anim_phase(&mut self, i: u8) {
+		self.coord_anim_phase = i;
+	}
+	fn coord_anim_index(&self) -> usize {
+		self.coord_anim_index
+	}
+
+	fn debug(&self) -> &String {
+		&self.debug
+	}
+	fn set_debug(&mut self, s: String) {
+		self.debug = s;
+	}
+
+	// ---
+
+	/**
+	 * Number keys switch to a sequence from the list (immediately; sequences can't be tweened)
+	 */
+	fn start_coord_anim(&mut self, index: usize) -> bool {
+		if index >= self.sequence_list.len() {
+			return false;
+		}
+		self.coord_anim_index = index;
+		self.specs.fractal_type = FractalType::Lyapunov(self.sequence_list.get(index));
+		self.dirty_fractal_checker.force_dirty();
+		true
+	}
+
+	fn update(&mut self) {
+		self.do_update();  // 'super'
+	}
+
+	fn do_dirty_fractal_check(&mut self) -> bool{
+		let v = vec![self.position_animator.value.x, self.position_animator.value.y,
+			self.width_animator.value, self.rotation_animator.value];
+		self.dirty_fractal_checker.do_check(v)
+	}
+}
@@ -9,7 +9,8 @@ use leelib::dirtychecker::DirtyChecker;
 use fract::constants;
 use fract::fractalcalc::{FractalSpecs, FractalType};
 use fract::Asciifier;
-use fract::exposure::{ExposureInfo};
+use fract::exposure::{ExposureInfo, ExposureMode, CurveAnimator};
+use fract::progressive::Progressive;
 use fract::{CoordList, Three64};
 use fract::view::View;
 
@@ -20,17 +21,23 @@ pub struct MandelView  {
 	specs: FractalSpecs,
     asciifier: Asciifier,
     fractal_matrix: Matrix<u16>,
+    family_matrix: Matrix<u8>,
+    edge_matrix: Matrix<char>,
     index_matrix: Matrix<u8>,
 
 	position_animator: Animator<Vector2f>,
 	width_animator: Animator<f64>,
 	rotation_animator: Animator<f64>,
+	progressive: Progressive,
 
     exposure_info: ExposureInfo, 
 	exposure_floor_animator: Animator<f64>,
 	exposure_ceil_animator: Animator<f64>,
 	dirty_exposure_checker: DirtyChecker,
 	use_exposure: bool,
+	exposure_mode: ExposureMode,
+	exposure_curve: CurveAnimator,
+	exposure_lock: Option<(f64, f64)>,
 
 	coord_anim_index: usize,
 	coord_anim_phase: u8,
@@ -50,17 +57,23 @@ impl MandelView {
 			specs: specs,
 		    asciifier: Asciifier::new(0.0, specs.max_val as f64),
 		    fractal_matrix: Matrix::new(matrix_w, matrix_h),
+		    family_matrix: Matrix::new(matrix_w, matrix_h),
+		    edge_matrix: Matrix::new(matrix_w, matrix_h),
 		    index_matrix: Matrix::new(matrix_w, matrix_h),
 
 			position_animator: Animator::<Vector2f>::new(Vector2f::new(0.0, 0.0), Anim::None),
 			width_animator: Animator::<f64>::new(specs.default_width, Anim::None),
 			rotation_animator: Animator::<f64>::new(0.0, Anim::None),
+			progressive: Progressive::new(),
 
 			exposure_floor_animator: Animator::<f64>::new( 0.0, Anim::Target { target: 0.0, coefficient: 0.1, epsilon: Some(0.01) } ),
 			exposure_ceil_animator: Animator::<f64>::new( specs.max_val as f64, Anim::Target { target: specs.max_val as f64, coefficient: 0.1, epsilon: Some(0.01) } ),
-			exposure_info: ExposureInfo { floor: 0, ceil: specs.max_val as usize, bias: 0.0 },
+			exposure_info: ExposureInfo { floor: 0, ceil: specs.max_val as usize, bias: 0.0, curve: Vec::new() },
 			dirty_exposure_checker: DirtyChecker::new(2),
 			use_exposure: true,
+			exposure_mode: ExposureMode::Heuristic,
+			exposure_curve: CurveAnimator::new(),
+			exposure_lock: None,
 
 			coord_anim_phase: 0,
 			coord_anim_index: 0,
@@ -110,6 +123,18 @@ impl View for MandelView {
 	fn fractal_matrix_m(&mut self) -> &mut Matrix<u16> {
 		&mut self.fractal_matrix
 	}
+	fn family_matrix(&self) -> &Matrix<u8> {
+		&self.family_matrix
+	}
+	fn family_matrix_m(&mut self) -> &mut Matrix<u8> {
+		&mut self.family_matrix
+	}
+	fn edge_matrix(&self) -> &Matrix<char> {
+		&self.edge_matrix
+	}
+	fn edge_matrix_m(&mut self) -> &mut Matrix<char> {
+		&mut self.edge_matrix
+	}
 
 	fn specs(&self) -> &FractalSpecs {
 		&self.specs
@@ -133,6 +158,8 @@ impl View for MandelView {
 	}
 	fn set_matrix_size(&mut self, matrix_w: usize, matrix_h: usize) {
 	    self.fractal_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.family_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.edge_matrix = Matrix::new(matrix_w, matrix_h);
 	    self.index_matrix = Matrix::new(matrix_w, matrix_h);
 	    self.dirty_fractal_checker().force_dirty();
 	}
@@ -149,6 +176,12 @@ impl View for MandelView {
 	fn dirty_fractal_checker(&mut self) -> &mut DirtyChecker {
 		&mut self.dirty_fractal_checker
 	}
+	fn progressive(&self) -> &Progressive {
+		&self.progressive
+	}
+	fn progressive_m(&mut self) -> &mut Progressive {
+		&mut self.progressive
+	}
 	
 	fn use_exposure(&self) -> bool {
 		self.use_exposure
@@ -171,6 +204,24 @@ impl View for MandelView {
 	fn dirty_exposure_checker(&mut self) -> &mut DirtyChecker {
 		&mut self.dirty_exposure_checker
 	}
+	fn exposure_mode(&self) -> ExposureMode {
+		self.exposure_mode
+	}
+	fn set_exposure_mode(&mut self, mode: ExposureMode) {
+		self.exposure_mode = mode;
+	}
+	fn exposure_curve(&self) -> &CurveAnimator {
+		&self.exposure_curve
+	}
+	fn exposure_curve_m(&mut self) -> &mut CurveAnimator {
+		&mut self.exposure_curve
+	}
+	fn exposure_lock(&self) -> Option<(f64, f64)> {
+		self.exposure_lock
+	}
+	fn set_exposure_lock(&mut self, lock: Option<(f64, f64)>) {
+		self.exposure_lock = lock;
+	}
 	
 	fn coord_anim_phase(&self) -> u8 {
 		self.coord_anim_phase
@@ -210,7 +261,7 @@ impl View for MandelView {
 	}
 	
 	fn do_dirty_fractal_check(&mut self) -> bool {
-		let v = vec![self.position_animator.value.x, self.position_animator.value.x, 
+		let v = vec![self.position_animator.value.x, self.position_animator.value.y, 
 			self.width_animator.value, self.rotation_animator.value];
 		self.dirty_fractal_checker.do_check(v)
 	}
@@ -7,5 +7,17 @@ pub use self::mandelview::MandelView;
 pub mod juliaview;
 pub use self::juliaview::JuliaView;
 
+pub mod newtonview;
+pub use self::newtonview::NewtonView;
+
+pub mod buddhaview;
+pub use self::buddhaview::BuddhaView;
+
+pub mod lyapunovview;
+pub use self::lyapunovview::LyapunovView;
+
+pub mod pointcloudview;
+pub use self::pointcloudview::PointCloudView;
+
 pub mod views;
 pub use self::views::Views;
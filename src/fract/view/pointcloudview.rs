@@ -0,0 +1,390 @@
+extern crate num;
+extern crate num_cpus;
+extern crate time;
+
+use std::cmp;
+use self::time::PreciseTime;
+use leelib::vector2::Vector2f;
+use leelib::matrix::Matrix;
+use leelib::animator::{Animator, Anim};
+use leelib::dirtychecker::DirtyChecker;
+use fract::constants;
+use fract::fractalcalc::{FractalCalc, FractalSpecs, FractalType, CalcStats};
+use fract::buddhabrot::{Buddhabrot, Density};
+use fract::attractor::{Attractor, PointSystem};
+use fract::CoordList;
+use fract::Asciifier;
+use fract::dither::Dither;
+use fract::exposure::{ExposureInfo, ExposureMode, CurveAnimator};
+use fract::progressive::Progressive;
+use fract::view::View;
+
+
+// the number of points in the first batch after the viewport changes
+const INITIAL_BATCH_SIZE: usize = 50_000;
+
+// plotting stops once this many points have accumulated, per cell
+const MAX_POINTS_PER_CELL: u64 = 5_000;
+
+// margin around the system's bounds when fitting it to the matrix
+const FIT_MARGIN: f64 = 1.1;
+
+
+/**
+ * Point-cloud rendering of strange attractors and iterated function systems (see `Attractor`), 
+ * which, like `BuddhaView`, converges over successive frames and starts over whenever the viewport 
+ * (or the system's parameters) change
+ */
+pub struct PointCloudView  {
+
+	// members backed by trait getter/setters:
+	specs: FractalSpecs,
+    asciifier: Asciifier,
+    fractal_matrix: Matrix<u16>,
+    family_matrix: Matrix<u8>,
+    edge_matrix: Matrix<char>,
+    index_matrix: Matrix<u8>,
+
+	position_animator: Animator<Vector2f>,
+	width_animator: Animator<f64>,
+	rotation_animator: Animator<f64>,
+	progressive: Progressive,  // (unused, since `calculate()` is overridden)
+
+    exposure_info: ExposureInfo,
+	exposure_floor_animator: Animator<f64>,
+	exposure_ceil_animator: Animator<f64>,
+	dirty_exposure_checker: DirtyChecker,
+	use_exposure: bool,
+	exposure_mode: ExposureMode,
+	exposure_curve: CurveAnimator,
+	exposure_lock: Option<(f64, f64)>,
+
+	coord_anim_index: usize,
+	coord_anim_phase: u8,
+
+	debug:String,
+
+	// struct-specific members:
+	density: Density,
+	points_per_usec: f64,  // measured from the previous batch
+	batch_count: u32,
+	system_list: CoordList<PointSystem>,
+	// tweens the system's parameters from 'from' to 'to' (the animator goes from 0 to 1)
+	system_from: PointSystem,
+	system_to: PointSystem,
+	system_animator: Animator<f64>,
+	dirty_fractal_checker: DirtyChecker,
+}
+
+impl PointCloudView {
+
+	pub fn new(matrix_w: usize, matrix_h: usize, specs: FractalSpecs) -> Self {
+
+		let system = match specs.fractal_type {
+			FractalType::PointCloud(system) => system,
+			_ => panic!("PointCloudView requires FractalType::PointCloud")
+		};
+
+		let mut view = PointCloudView {
+
+			specs: specs,
+		    asciifier: Asciifier::new(0.0, specs.max_val as f64),
+		    fractal_matrix: Matrix::new(matrix_w, matrix_h),
+		    family_matrix: Matrix::new(matrix_w, matrix_h),
+		    edge_matrix: Matrix::new(matrix_w, matrix_h),
+		    index_matrix: Matrix::new(matrix_w, matrix_h),
+
+			position_animator: Animator::<Vector2f>::new(specs.default_center, Anim::None),
+			width_animator: Animator::<f64>::new(specs.default_width, Anim::None),
+			rotation_animator: Animator::<f64>::new(0.0, Anim::None),
+			progressive: Progressive::new(),
+			dirty_fractal_checker: DirtyChecker::new(5),  // note, 1 more val than mandelbrot version
+
+			exposure_floor_animator: Animator::<f64>::new( 0.0, Anim::Target { target: 0.0, coefficient: 0.1, epsilon: Some(0.01) } ),
+			exposure_ceil_animator: Animator::<f64>::new( specs.max_val as f64, Anim::Target { target: specs.max_val as f64, coefficient: 0.1, epsilon: Some(0.01) } ),
+			exposure_info: ExposureInfo { floor: 0, ceil: specs.max_val as usize, bias: 0.0, curve: Vec::new() },
+			dirty_exposure_checker: DirtyChecker::new(2),
+			use_exposure: true,
+			exposure_mode: ExposureMode::Heuristic,
+			exposure_curve: CurveAnimator::new(),
+			exposure_lock: None,
+
+			coord_anim_phase: 0,
+			coord_anim_index: 0,
+
+			debug: "".to_string(),
+
+			density: Density::new(matrix_w, matrix_h, 1),
+			points_per_usec: 0.0,
+			batch_count: 0,
+			system_list: CoordList::<PointSystem>::new(constants::POINT_SYSTEMS_TEXT),
+			system_from: system,
+			system_to: system,
+			system_animator: Animator::<f64>::new(1.0, Anim::None),
+		};
+		view.fit_to_matrix(system);
+		view.position_animator.value = view.specs.default_center;
+		view.width_animator.value = view.specs.default_width;
+		view
+	}
+
+	fn system(&self) -> PointSystem {
+		match self.specs.fractal_type {
+			FractalType::PointCloud(system) => system,
+			_ => panic!()
+		}
+	}
+
+	/**
+	 * Sets the default center and width so that the system's bounds fit in the matrix
+	 */
+	fn fit_to_matrix(&mut self, system: PointSystem) {
+		let (center, size) = system.bounds();
+		let height_per_width = FractalCalc::get_height(&self.specs, 
+				self.fractal_matrix.width(), self.fractal_matrix.height(), 1.0);
+		let w = size.x.max(size.y / height_per_width) * FIT_MARGIN;
+		self.specs.default_center = center;
+		self.specs.default_width = w;
+	}
+
+	fn reset_density(&mut self) {
+		let w = self.fractal_matrix.width();
+		let h = self.fractal_matrix.height();
+		self.density = Density::new(w, h, 1);
+	}
+
+	fn is_converged(&self) -> bool {
+		let num_cells = (self.fractal_matrix.width() * self.fractal_matrix.height()) as u64;
+		self.density.num_samples >= num_cells * MAX_POINTS_PER_CELL
+	}
+}
+
+impl View for PointCloudView {
+
+	fn specs(&self) -> &FractalSpecs {
+		&self.specs
+	}
+	fn specs_m(&mut self) -> &mut FractalSpecs {
+		&mut self.specs
+	}
+
+	fn fractal_matrix(&self) -> &Matrix<u16> {
+		&self.fractal_matrix
+	}
+	fn fractal_matrix_m(&mut self) -> &mut Matrix<u16> {
+		&mut self.fractal_matrix
+	}
+	fn family_matrix(&self) -> &Matrix<u8> {
+		&self.family_matrix
+	}
+	fn family_matrix_m(&mut self) -> &mut Matrix<u8> {
+		&mut self.family_matrix
+	}
+	fn edge_matrix(&self) -> &Matrix<char> {
+		&self.edge_matrix
+	}
+	fn edge_matrix_m(&mut self) -> &mut Matrix<char> {
+		&mut self.edge_matrix
+	}
+
+    fn index_matrix(&self) -> &Matrix<u8> {
+    	&self.index_matrix
+    }
+    fn index_matrix_m(&mut self) -> &mut Matrix<u8> {
+       	&mut self.index_matrix
+    }
+
+	fn set_matrix_size(&mut self, matrix_w: usize, matrix_h: usize) {
+	    self.fractal_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.family_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.edge_matrix = Matrix::new(matrix_w, matrix_h);
+	    self.index_matrix = Matrix::new(matrix_w, matrix_h);
+	    let system = self.system_to;
+	    self.fit_to_matrix(system);
+	    self.dirty_fractal_checker().force_dirty();
+	}
+
+    fn asciifier(&self) -> &Asciifier {
+    	&self.asciifier
+    }
+	fn asciifier_m(&mut self) -> &mut Asciifier {
+		&mut self.asciifier
+	}
+
+	fn position_animator(&mut self) -> &mut Animator<Vector2f> {
+		&mut self.position_animator
+	}
+	fn width_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.width_animator
+	}
+	fn rotation_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.rotation_animator
+	}
+	fn dirty_fractal_checker(&mut self) -> &mut DirtyChecker {
+		&mut self.dirty_fractal_checker
+	}
+	fn progressive(&self) -> &Progressive {
+		&self.progressive
+	}
+	fn progressive_m(&mut self) -> &mut Progressive {
+		&mut self.progressive
+	}
+
+	fn use_exposure(&self) -> bool {
+		self.use_exposure
+	}
+	fn toggle_use_exposure(&mut self) {
+		self.use_exposure = ! self.use_exposure;
+	}
+	fn exposure_info(&self) -> &ExposureInfo {
+		&self.exposure_info
+	}
+	fn set_exposure_info(&mut self, info: ExposureInfo) {
+		self.exposure_info = info;
+	}
+	fn exposure_floor_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.exposure_floor_animator
+	}
+	fn exposure_ceil_animator(&mut self) -> &mut Animator<f64> {
+		&mut self.exposure_ceil_animator
+	}
+	fn dirty_exposure_checker(&mut self) -> &mut DirtyChecker {
+		&mut self.dirty_exposure_checker
+	}
+	fn exposure_mode(&self) -> ExposureMode {
+		self.exposure_mode
+	}
+	fn set_exposure_mode(&mut self, mode: ExposureMode) {
+		self.exposure_mode = mode;
+	}
+	fn exposure_curve(&self) -> &CurveAnimator {
+		&self.exposure_curve
+	}
+	fn exposure_curve_m(&mut self) -> &mut CurveAnimator {
+		&mut self.exposure_curve
+	}
+	fn exposure_lock(&self) -> Option<(f64, f64)> {
+		self.exposure_lock
+	}
+	fn set_exposure_lock(&mut self, lock: Option<(f64, f64)>) {
+		self.exposure_lock = lock;
+	}
+
+	fn coord_anim_phase(&self) -> u8 {
+		self.coord_anim_phase
+	}
+	fn set_coord_anim_phase(&mut self, i: u8) {
+		self.coord_anim_phase = i;
+	}
+	fn coord_anim_index(&self) -> usize {
+		self.coord_anim_index
+	}
+
+	fn debug(&self) -> &String {
+		&self.debug
+	}
+	fn set_debug(&mut self, s: String) {
+		self.debug = s;
+	}
+
+	// ---
+
+	/**
+	 * Number keys select a system from the list, and move to its bounds. 
+	 * Parameters get tweened when it's of the same kind as the current one; otherwise it's switched to immediately.
+	 */
+	fn start_coord_anim(&mut self, index: usize) -> bool {
+		if index >= self.system_list.len() || (self.coord_anim_phase > 0 && index == self.coord_anim_index) {
+			return false;
+		}
+		let current = self.system();
+		let target = self.system_list.get(index);
+
+		self.fit_to_matrix(target);
+		self.anim_to_home();
+
+		self.coord_anim_index = index;
+		if target.kind == current.kind && target.kind.has_params() {
+			self.coord_anim_phase = 1;
+			self.system_from = current;
+			self.system_to = target;
+			self.system_animator.value = 0.0;
+			let anim = Anim::Target { target: 1.0, coefficient: constants::TARGET_COEF * 0.5, epsilon: Some(0.001) };
+			self.system_animator.set_anim(anim);
+		} else {
+			self.system_from = target;
+			self.system_to = target;
+			self.specs.fractal_type = FractalType::PointCloud(target);
+			self.dirty_fractal_checker.force_dirty();
+		}
+		true
+	}
+
+	fn update(&mut self) {
+
+		self.do_update();  // 'super'
+
+		if self.coord_anim_phase == 1 {
+			let t = match self.system_animator.anim() {
+				&Anim::Target { .. } => {
+					self.system_animator.update();
+					self.system_animator.value
+				},
+				_ => {
+					// landed
+					self.coord_anim_phase = 0;
+					1.0
+				}
+			};
+			let system = PointSystem::interpolate(&self.system_from, &self.system_to, t);
+			self.specs.fractal_type = FractalType::PointCloud(system);
+		}
+	}
+
+	fn do_dirty_fractal_check(&mut self) -> bool{
+		let v = vec![self.position_animator.value.x, self.position_animator.value.y,
+			self.width_animator.value, self.rotation_animator.value,
+			self.system_animator.value];
+		self.dirty_fractal_checker.do_check(v)
+	}
+
+	/**
+	 * Plots as many points as the budget allows, and rewrites the fractal matrix from the accumulated density
+	 */
+	fn calculate(&mut self, budget_usec: i64) -> CalcStats {
+
+		let restart = self.do_dirty_fractal_check();
+		if restart {
+			self.reset_density();
+			Dither::reset(&mut self.index_matrix);
+		}
+
+		let is_dirty = if restart || ! self.is_converged() {
+			let num_points = if self.points_per_usec > 0.0 {
+				cmp::max((self.points_per_usec * budget_usec as f64) as usize, 1)
+			} else {
+				INITIAL_BATCH_SIZE
+			};
+
+			let pos = self.position_animator.value;
+			let w = self.width_animator.value;
+			let r = self.rotation_animator.value;
+			let system = self.system();
+			self.batch_count += 1;
+
+			let t = PreciseTime::now();
+			Attractor::accumulate(&self.specs, system, pos, w, r, &mut self.density, num_points, self.batch_count);
+			let usec = t.to(PreciseTime::now()).num_microseconds().unwrap();
+			self.points_per_usec = num_points as f64 / cmp::max(usec, 1) as f64;
+
+			Buddhabrot::write_matrix(&self.density, self.specs.max_val, &mut self.fractal_matrix, &mut self.family_matrix);
+			true
+		} else {
+			false
+		};
+
+		self.update_exposure(is_dirty);
+
+		CalcStats::new()
+	}
+}
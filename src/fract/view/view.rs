@@ -1,15 +1,21 @@
 extern crate num; 
 extern crate num_cpus;
 
+use std::mem;
+use self::num::complex::Complex64;
 use leelib::math;
 use leelib::vector2::Vector2f;
 use leelib::matrix::Matrix;
 use leelib::animator::{Animator, Anim};
 use leelib::dirtychecker::DirtyChecker;
 use fract::constants;
-use fract::fractalcalc::{FractalCalc, FractalSpecs};
+use fract::fractalcalc::{FractalCalc, FractalSpecs, CalcStats};
 use fract::Asciifier;
-use fract::exposure::{ExposureUtil, ExposureInfo};
+use fract::asciifier::Mapping;
+use fract::dither::Dither;
+use fract::exposure::{ExposureUtil, ExposureInfo, ExposureMode, CurveAnimator};
+use fract::progressive::Progressive;
+use fract::seedpath::SeedPathMode;
 
 
 pub trait View {
@@ -20,14 +26,20 @@ pub trait View {
     fn asciifier_m(&mut self) -> &mut Asciifier;
 	fn fractal_matrix(&self) -> &Matrix<u16>;
 	fn fractal_matrix_m(&mut self) -> &mut Matrix<u16>;
+	fn family_matrix(&self) -> &Matrix<u8>;
+	fn family_matrix_m(&mut self) -> &mut Matrix<u8>;
     fn index_matrix(&self) -> &Matrix<u8>;
     fn index_matrix_m(&mut self) -> &mut Matrix<u8>;
+	fn edge_matrix(&self) -> &Matrix<char>;
+	fn edge_matrix_m(&mut self) -> &mut Matrix<char>;
 	fn set_matrix_size(&mut self, matrix_w: usize, matrix_h: usize);
 	
 	fn position_animator(&mut self) -> &mut Animator<Vector2f>;
 	fn width_animator(&mut self) -> &mut Animator<f64>;
 	fn rotation_animator(&mut self) -> &mut Animator<f64>;
 	fn dirty_fractal_checker(&mut self) -> &mut DirtyChecker;
+	fn progressive(&self) -> &Progressive;
+	fn progressive_m(&mut self) -> &mut Progressive;
 	
 	fn exposure_info(&self) -> &ExposureInfo;
 	fn set_exposure_info(&mut self, info: ExposureInfo); 
@@ -36,6 +48,22 @@ pub trait View {
 	fn use_exposure(&self) -> bool;
 	fn toggle_use_exposure(&mut self);
 	fn dirty_exposure_checker(&mut self) -> &mut DirtyChecker;
+	fn exposure_mode(&self) -> ExposureMode;
+	fn set_exposure_mode(&mut self, mode: ExposureMode);
+	fn exposure_curve(&self) -> &CurveAnimator;
+	fn exposure_curve_m(&mut self) -> &mut CurveAnimator;
+	// manual floor and ceil, which take the place of auto-exposure while set
+	fn exposure_lock(&self) -> Option<(f64, f64)>;
+	fn set_exposure_lock(&mut self, lock: Option<(f64, f64)>);
+
+	/**
+	 * Equalization only applies to the default mapping; distance estimates and signed values 
+	 * have their own purpose-made exposure (see `ExposureUtil::calc_for_specs()`).
+	 * Locked exposure uses floor and ceil instead.
+	 */
+	fn is_equalizing(&self) -> bool {
+		self.use_exposure() && self.exposure_lock().is_none() && self.exposure_mode() == ExposureMode::Equalize && self.specs().mapping() == Mapping::Bias
+	}
 
 	fn set_exposure_anim_targets(&mut self, floor: f64, ceil: f64) {
 		let a = Anim::Target { target: floor as f64, coefficient: 0.12, epsilon: Some(0.5) };
@@ -50,6 +78,24 @@ pub trait View {
 	
 	fn start_coord_anim(&mut self, index: usize) -> bool;
 	
+	/**
+	 * Tweens the julia seed, for views which have one
+	 */
+	fn tween_julia_c(&mut self, _c: Complex64) {
+	}
+
+	// how the julia seed gets from one value to another, and whether it's orbiting continuously
+	fn seed_path_mode(&self) -> SeedPathMode {
+		SeedPathMode::Straight
+	}
+	fn set_seed_path_mode(&mut self, _mode: SeedPathMode) {
+	}
+	fn is_orbiting(&self) -> bool {
+		false
+	}
+	fn set_orbiting(&mut self, _b: bool) {
+	}
+
 	fn stop_coord_anim(&mut self) {
 		if self.coord_anim_phase() > 0 {
 			self.set_coord_anim_phase(0);
@@ -97,15 +143,16 @@ pub trait View {
 		// position
 		self.position_animator().update();
 
-		// position bounds check
+		// position bounds check (around the default center)
 		let mut b = false;
+		let center = self.specs().default_center;
 		let w = self.specs().default_width / 2.0;
-		if self.position_animator().value.x < -w {
-			self.position_animator().value.x = -w;
+		if self.position_animator().value.x < center.x - w {
+			self.position_animator().value.x = center.x - w;
 			b = true;
 		}
-		if self.position_animator().value.x > w {
-			self.position_animator().value.x = w;
+		if self.position_animator().value.x > center.x + w {
+			self.position_animator().value.x = center.x + w;
 			b = true;
 		}
 		let mw = self.fractal_matrix().width();
@@ -113,12 +160,12 @@ pub trait View {
 		let dw = self.specs().default_width;
 		let h = FractalCalc::get_height(&self.specs(), mw, mh, dw);
 		let h = h / 2.0;
-		if self.position_animator().value.y < -h {
-			self.position_animator().value.y = -h;
+		if self.position_animator().value.y < center.y - h {
+			self.position_animator().value.y = center.y - h;
 			b = true;
 		}
-		if self.position_animator().value.y > h {
-			self.position_animator().value.y = h;
+		if self.position_animator().value.y > center.y + h {
+			self.position_animator().value.y = center.y + h;
 			b = true;
 		}
 		if b {
@@ -136,10 +183,13 @@ pub trait View {
 		}
 
 		// exposure 
-		let (f, c) = if self.use_exposure() {
-			(self.exposure_info().floor as f64, self.exposure_info().ceil as f64)
-		} else {
-			(0.0, self.specs().max_val as f64)
+		let (f, c) = match self.exposure_lock() {
+			Some(lock) => lock,
+			None => if self.use_exposure() {
+				(self.exposure_info().floor as f64, self.exposure_info().ceil as f64)
+			} else {
+				(0.0, self.specs().max_val as f64)
+			}
 		};
 		self.set_exposure_anim_targets(f, c);
 		self.exposure_floor_animator().update();
@@ -148,47 +198,116 @@ pub trait View {
 	
 	fn do_dirty_fractal_check(&mut self) -> bool;
 
-	fn calculate(&mut self) {
+	/**
+	 * budget_usec - the time available for calculating the fractal matrix during this frame 
+	 * (see `Progressive`)
+	 *
+	 * Returns the number of cells that were calculated
+	 */
+	fn calculate(&mut self, budget_usec: i64) -> CalcStats {
 
-		let dirty1 = self.do_dirty_fractal_check();
-		if dirty1 {
+		let restart = self.do_dirty_fractal_check();
+		if restart {
+			Dither::reset(self.index_matrix_m());
+		}
+		let dirty1 = if restart || ! self.progressive().is_complete() {
 			// calc fractal matrix using positional info
 			let pos = self.position_animator().value.clone();
 			let w = self.width_animator().value;
 			let r = self.rotation_animator().value;
 			let specs = self.specs().clone();
-			FractalCalc::write_matrix(&specs, pos, w, r, &mut self.fractal_matrix_m());
+			
+			// rem, can't borrow both the matrix and the progressive instance mutably from self
+			let mut matrix = mem::replace(self.fractal_matrix_m(), Matrix::new(1, 1));
+			let mut families = mem::replace(self.family_matrix_m(), Matrix::new(1, 1));
+			let b = self.progressive_m().render(&specs, pos, w, r, &mut matrix, &mut families, restart, budget_usec);
+			*self.fractal_matrix_m() = matrix;
+			*self.family_matrix_m() = families;
+			b
+		} else {
+			false
+		};
+		
+		self.update_exposure(dirty1);
+		
+		self.progressive_m().take_stats()
+	}
 	
+	/**
+	 * Recalculates exposure info when the fractal matrix has changed, 
+	 * applies it to the asciifier, and updates the index matrix
+	 */
+	fn update_exposure(&mut self, is_matrix_dirty: bool) {
+
+		// (also recalc when equalization has been turned on or off)
+		let is_stale = self.is_equalizing() != (self.exposure_info().curve.len() > 0);
+		let recalc = is_matrix_dirty || is_stale;
+		if recalc {
 			// calc 'exposure info' from matrix
-			let info = ExposureUtil::calc(&self.fractal_matrix(), self.specs().max_val, 0.040, 0.010);
+			let mut info = ExposureUtil::calc_for_specs(&self.fractal_matrix(), self.specs());
+			if self.is_equalizing() {
+				info.curve = ExposureUtil::calc_equalized(&self.fractal_matrix(), self.specs().max_val, info.floor, info.ceil);
+			}
 			self.set_exposure_info(info);
 		}
+		
+		let mapping = self.specs().mapping();
+		self.asciifier_m().set_mapping(mapping);
 
 		// apply exposure info to asciifer		
 		let f = self.exposure_floor_animator().value;
 		let c = self.exposure_ceil_animator().value;
 		self.asciifier_m().set_floor_ceil(f, c);
 
-		let bias = if self.use_exposure() {
+		let bias = if self.use_exposure() && self.exposure_lock().is_none() {
 			if self.exposure_info().bias > 0.0 { self.exposure_info().bias } else { 0.0 }
 		} else {
 			0.0
 		};
 		self.asciifier_m().set_bias(bias);  
 
+		// equalization curve, which eases from (and back to) the asciifier's own mapping
+		if recalc {
+			let max_val = self.specs().max_val;
+			let mapping_curve = self.asciifier().to_curve(max_val);
+			if self.is_equalizing() {
+				let target = self.exposure_info().curve.clone();
+				self.exposure_curve_m().set_target(target, mapping_curve);
+			} else {
+				self.exposure_curve_m().release(mapping_curve);
+			}
+		}
+		let dirty3 = self.exposure_curve_m().update(0.12, 0.002);
+
 		let v = vec![self.exposure_floor_animator().value, self.exposure_ceil_animator().value];
 		let dirty2 = self.dirty_exposure_checker().do_check(v);
-		if  dirty1 || dirty2 {
+		if  is_matrix_dirty || dirty2 || dirty3 {
 			self.calc_index_matrix();
 		}
 
 		// self.set_debug(format!(" exp {} {} {}", self.exposure_info().floor, self.exposure_info().ceil, self.exposure_info().bias));
 	}
 	
+	/**
+	 * Locks exposure at the current floor and ceil, if it isn't already, 
+	 * and then shifts the window by `shift` and scales its width by `scale` (both relative to its width)
+	 */
+	fn adjust_exposure_lock(&mut self, shift: f64, scale: f64) {
+		let (f, c) = match self.exposure_lock() {
+			Some(lock) => lock,
+			None => (self.exposure_floor_animator().value, self.exposure_ceil_animator().value)
+		};
+		let mid = (f + c) / 2.0 + (c - f) * shift;
+		let half = ((c - f) / 2.0 * scale).max(1.0);
+		let f = (mid - half).max(0.0);
+		self.set_exposure_lock(Some((f, f + half * 2.0)));
+	}
+
 	fn anim_to_home(&mut self) {
 		self.stop_coord_anim();
+		let center = self.specs().default_center;
 		self.position_animator().set_anim( 
-				Anim::Target { target: Vector2f { x: 0.0, y: 0.0 }, coefficient: constants::TARGET_COEF, epsilon: None });					
+				Anim::Target { target: center, coefficient: constants::TARGET_COEF, epsilon: None });					
 		let dw = self.specs().default_width;
 		self.width_animator().set_anim( Anim::Target { 
 				target: dw, coefficient: constants::TARGET_COEF, epsilon: None } );
@@ -205,13 +324,16 @@ pub trait View {
 		
 		assert!(self.fractal_matrix().width() == self.index_matrix().width() && 
 				self.fractal_matrix().height() == self.index_matrix().height());
-		 
-		for y in 0..self.fractal_matrix().height() {
-			for x in 0..self.fractal_matrix().width() {
-				let val =  self.fractal_matrix().get(x, y);
-				let i = self.asciifier().to_char_index(val as f64);
-				self.index_matrix_m().set(x, y, i);
-			}
+		
+		// rem, can't borrow the index matrix mutably while borrowing the rest of self 
+		let mut indices = mem::replace(self.index_matrix_m(), Matrix::new(1, 1));
+		let mut edges = mem::replace(self.edge_matrix_m(), Matrix::new(1, 1));
+		{
+			let curve = if self.exposure_curve().value.len() > 0 { Some(&self.exposure_curve().value) } else { None };
+			let element_ar = self.specs().element_ar;
+			self.asciifier().write_index_matrix(self.fractal_matrix(), curve, element_ar, &mut indices, &mut edges);
 		}
+		*self.index_matrix_m() = indices;
+		*self.edge_matrix_m() = edges;
 	}
 }	
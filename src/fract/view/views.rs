@@ -2,16 +2,20 @@ use fract::view::View;
 
 /**
  * Simple vector wrapper of boxed Views 
+ *
+ * When `panes` is set, its two views get drawn side by side (left, right), 
+ * and `index` is whichever of the two has focus (ie, receives input)
  */
 pub struct Views {
 	pub vec: Vec<Box<View>>,
 	pub index: usize,
+	pub panes: Option<(usize, usize)>,
 }
 
 impl Views {
 	
 	pub fn new() -> Self {
-		Views { vec: Vec::new(), index:0 }
+		Views { vec: Vec::new(), index:0, panes: None }
 	}
 	
 	pub fn get(&mut self) -> &mut View {
@@ -27,4 +31,5 @@ impl Views {
 	pub fn get_num_im(&self, i: usize) -> &View {
 		&(*self.vec[i])
 	}
+
 }
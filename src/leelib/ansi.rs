@@ -10,3 +10,28 @@ pub fn move_cursor(col:i32, row:i32) -> String {
 	// rem, ansi row and col are 1-indexed
 	format!("\x1b[{row};{col}H", row = row + 1, col = col + 1)
 }
+
+/**
+ * OSC 52: asks the terminal to put `text` on the system clipboard 
+ * (supported by most modern terminals, and by tmux with 'set-clipboard on')
+ */
+pub fn copy_to_clipboard(text: &str) -> String {
+	format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+	let table = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+	let mut s = String::new();
+	for chunk in bytes.chunks(3) {
+		let b = [chunk[0], if chunk.len() > 1 { chunk[1] } else { 0 }, if chunk.len() > 2 { chunk[2] } else { 0 }];
+		let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | (b[2] as u32);
+		for i in 0..4 {
+			if i <= chunk.len() {
+				s.push(table[((n >> (18 - 6 * i)) & 63) as usize] as char);
+			} else {
+				s.push('=');
+			}
+		}
+	}
+	s
+}
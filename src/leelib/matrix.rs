@@ -65,6 +65,15 @@ impl<T:Clone + Default> Matrix<T> {
 			}
 		}
 	}
+
+	/**
+	 * Returns a copy of the rows from 'start_y' to 'start_y + height'
+	 * (ie, the inverse of `copy_from`)
+	 */
+	pub fn section(&self, start_y: usize, height: usize) -> Matrix<T> {
+		let vec: Vec<Vec<T>> = self.vec[start_y..(start_y + height)].to_vec();
+		Matrix { vec: vec, index: 0 }
+	}
 }
 
 impl<T: fmt::Display> fmt::Debug for Matrix<T>  {
@@ -3,4 +3,5 @@ pub mod matrix;
 pub mod vector2;
 pub mod dirtychecker;
 pub mod math;
-pub mod ansi;
\ No newline at end of file
+pub mod ansi;
+pub mod terminal;
\ No newline at end of file
@@ -0,0 +1,143 @@
+extern crate libc;
+
+use std::io::{self, Read, Write};
+use std::mem;
+
+
+/**
+ * Tries to find the width/height ratio of a character cell of the terminal, in pixels.
+ *
+ * First asks the tty driver (TIOCGWINSZ), whose pixel fields are often left at 0;
+ * failing that, asks the terminal itself using the 'CSI 14 t' (text area size in pixels)
+ * and 'CSI 18 t' (text area size in characters) reports, which not all terminals answer.
+ *
+ * Must be called before anything else starts reading from stdin.
+ */
+pub fn get_cell_aspect_ratio() -> Option<f64> {
+	match get_size_ioctl() {
+		Some(size) => return size.cell_aspect_ratio(),
+		None => {}
+	}
+	match get_size_query() {
+		Some(size) => size.cell_aspect_ratio(),
+		None => None
+	}
+}
+
+
+struct TermSize {
+	cols: u32,
+	rows: u32,
+	px_width: u32,
+	px_height: u32,
+}
+
+impl TermSize {
+	fn cell_aspect_ratio(&self) -> Option<f64> {
+		if self.cols == 0 || self.rows == 0 || self.px_width == 0 || self.px_height == 0 {
+			return None;
+		}
+		let cell_w = self.px_width as f64 / self.cols as f64;
+		let cell_h = self.px_height as f64 / self.rows as f64;
+		Some(cell_w / cell_h)
+	}
+}
+
+
+fn get_size_ioctl() -> Option<TermSize> {
+	unsafe {
+		let mut ws: libc::winsize = mem::zeroed();
+		if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) != 0 {
+			return None;
+		}
+		if ws.ws_xpixel == 0 || ws.ws_ypixel == 0 {
+			return None;
+		}
+		Some(TermSize { cols: ws.ws_col as u32, rows: ws.ws_row as u32,
+				px_width: ws.ws_xpixel as u32, px_height: ws.ws_ypixel as u32 })
+	}
+}
+
+fn get_size_query() -> Option<TermSize> {
+
+	if unsafe { libc::isatty(libc::STDIN_FILENO) } == 0 {
+		return None;
+	}
+
+	// put tty in non-canonical, non-echoing mode, with reads timing out after 0.5s
+	let mut original: libc::termios = unsafe { mem::zeroed() };
+	if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+		return None;
+	}
+	let mut raw = original;
+	raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+	raw.c_cc[libc::VMIN] = 0;
+	raw.c_cc[libc::VTIME] = 5;
+	if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+		return None;
+	}
+
+	// reply looks like "ESC [ 4 ; height ; width t"
+	let px = query("\x1b[14t", 4);
+	// reply looks like "ESC [ 8 ; rows ; cols t"
+	let chars = match px {
+		Some(_) => query("\x1b[18t", 8),
+		None => None,
+	};
+
+	// (discards anything still pending, so that a late reply doesn't get read as keypresses)
+	unsafe { 
+		libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original);
+		libc::tcflush(libc::STDIN_FILENO, libc::TCIFLUSH);
+	}
+
+	match (px, chars) {
+		(Some((px_height, px_width)), Some((rows, cols))) => {
+			Some(TermSize { cols: cols, rows: rows, px_width: px_width, px_height: px_height })
+		},
+		_ => None
+	}
+}
+
+/**
+ * Writes `request` to stdout and parses a reply of the form "ESC [ <code> ; <a> ; <b> t"
+ */
+fn query(request: &str, code: u32) -> Option<(u32, u32)> {
+
+	print!("{}", request);
+	let _ = io::stdout().flush();
+
+	let mut reply = String::new();
+	let mut buf = [0u8; 1];
+	let stdin = io::stdin();
+	let mut handle = stdin.lock();
+	loop {
+		match handle.read(&mut buf) {
+			Ok(1) => {
+				let c = buf[0] as char;
+				reply.push(c);
+				if c == 't' || reply.len() > 32 {
+					break;
+				}
+			},
+			_ => break,  // timed out, terminal didn't respond
+		}
+	}
+
+	let start = match reply.find('[') {
+		Some(i) => i + 1,
+		None => return None,
+	};
+	if ! reply.ends_with('t') {
+		return None;
+	}
+	let v: Vec<&str> = reply[start..(reply.len() - 1)].split(';').collect();
+	if v.len() != 3 {
+		return None;
+	}
+	let nums: Vec<u32> = v.iter().filter_map(|s| s.parse::<u32>().ok()).collect();
+	if nums.len() != 3 || nums[0] != code {
+		return None;
+	}
+	Some((nums[1], nums[2]))
+}